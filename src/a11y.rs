@@ -0,0 +1,149 @@
+use pomodoro_timer::{AppState, PomodoroApp, TimerType, FIELD_LABELS};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+// Plain-line renderer for screen readers: no alternate screen, no cursor
+// movement, just one sentence per change plus a periodic time report
+pub struct Announcer {
+    last_context: String,
+    last_report: Instant,
+    interval: Duration,
+}
+
+impl Announcer {
+    pub fn new() -> Self {
+        Self {
+            last_context: String::new(),
+            last_report: Instant::now(),
+            interval: Duration::from_secs(60),
+        }
+    }
+
+    pub fn update(&mut self, app: &PomodoroApp) -> io::Result<()> {
+        let context = context(app);
+        let report_due = app.state == AppState::Running
+            && !app.paused
+            && self.last_report.elapsed() >= self.interval;
+
+        if context != self.last_context {
+            let line = if app.state == AppState::Running {
+                format!("{}, {}", context, remaining(app.secs_left()))
+            } else {
+                context.clone()
+            };
+            announce(&line)?;
+            self.last_context = context;
+            self.last_report = Instant::now();
+        } else if report_due {
+            announce(&remaining(app.secs_left()))?;
+            self.last_report = Instant::now();
+        }
+        Ok(())
+    }
+}
+
+// Raw mode needs an explicit carriage return
+fn announce(line: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "{}\r\n", line)?;
+    stdout.flush()
+}
+
+fn context(app: &PomodoroApp) -> String {
+    if app.quit_pending {
+        return "Quit this session? y to quit, any other key to keep going.".to_string();
+    }
+    if let Some(selected) = app.skip_reason_choice {
+        return format!(
+            "Why skip this break? {}, {} of {}. Up and down to choose, Enter to confirm, Escape for no reason.",
+            app.skip_reasons[selected],
+            selected + 1,
+            app.skip_reasons.len()
+        );
+    }
+    match app.state {
+        AppState::Setup => match app.inputs.get(app.focus_index) {
+            Some(input) => {
+                let value = if input.value.is_empty() {
+                    &input.placeholder
+                } else {
+                    &input.value
+                };
+                format!(
+                    "Setup. {}: {}. Tab for next field, Enter to start, a to toggle auto breaks, q to quit.",
+                    FIELD_LABELS[app.focus_index].0,
+                    value
+                )
+            }
+            None if app.label_focused() => format!(
+                "Setup. Task label: {}. Type any text, Tab for next field.",
+                if app.label_input.value.is_empty() { "none" } else { &app.label_input.value }
+            ),
+            None => match app.todos.get(app.todo_index) {
+                Some(task) => format!("Setup. Focus task: {}. Left and right to change.", task.text),
+                None => "Setup.".to_string(),
+            },
+        },
+        AppState::Running => {
+            let mut text = match app.timer_type {
+                TimerType::Work => {
+                    format!("Work session {} of {}", app.current_session, app.total_sessions)
+                }
+                TimerType::ShortBreak => "Short break".to_string(),
+                TimerType::LongBreak => "Long break".to_string(),
+            };
+            if app.paused {
+                text.push_str(", paused");
+            }
+            text
+        }
+        AppState::Ritual => format!(
+            "Focus ritual, step {} of {}: {}. Enter when done, s to skip.",
+            app.ritual_index + 1,
+            app.ritual.len(),
+            app.ritual.get(app.ritual_index).map_or("", String::as_str)
+        ),
+        AppState::BreakLeadIn => "Break starting in a few seconds. s to skip.".to_string(),
+        AppState::Overtime => format!(
+            "Work session {} time is up, counting overtime. s to take the break.",
+            app.current_session
+        ),
+        AppState::Stats => {
+            let summary = &app.stats;
+            if summary.all_time == 0 {
+                "Stats. No sessions yet. Escape to go back.".to_string()
+            } else {
+                format!(
+                    "Stats. {} pomodoros today, {} this week, {} all time. Escape to go back.",
+                    summary.today, summary.this_week, summary.all_time
+                )
+            }
+        }
+        AppState::WorkPrompt => format!(
+            "Break over. Work session {} of {} is next. Enter to start.",
+            app.current_session, app.total_sessions
+        ),
+        AppState::BreakPrompt => {
+            let kind = match app.next_break_type {
+                Some(TimerType::LongBreak) => "a long break",
+                _ => "a short break",
+            };
+            format!(
+                "Work session {} complete. Time for {}. Enter to start the break, s to skip.",
+                app.current_session, kind
+            )
+        }
+    }
+}
+
+fn remaining(secs: u64) -> String {
+    let (minutes, seconds) = (secs / 60, secs % 60);
+    let plural = |n: u64| if n == 1 { "" } else { "s" };
+    format!(
+        "{} minute{} {} second{} remaining",
+        minutes,
+        plural(minutes),
+        seconds,
+        plural(seconds)
+    )
+}
@@ -0,0 +1,55 @@
+use crate::audio::Audio;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+// One completion alert: the track the playlist picked, if any, and the
+// vibration, when it's on
+#[derive(Clone, Debug, PartialEq)]
+pub struct Alert {
+    pub track: Option<PathBuf>,
+    pub volume: f32,
+    pub vibrate: bool,
+    pub vibrate_command: Option<String>,
+}
+
+// Where completion alerts go. The timer decides when to alert; the sink
+// plays it, so the self-test and embedders can swap it out
+pub trait AlertSink: Send {
+    fn alert(&self, audio: &Audio, alert: Alert);
+}
+
+// The sound through the app's audio, plus the vibration
+pub struct SpeakerAlerts;
+
+impl AlertSink for SpeakerAlerts {
+    fn alert(&self, audio: &Audio, alert: Alert) {
+        if let Some(track) = alert.track {
+            audio.play(track, alert.volume);
+        }
+        if alert.vibrate {
+            crate::vibrate(alert.vibrate_command.as_deref());
+        }
+    }
+}
+
+// Keeps the alerts instead of playing them. Clones share the record, so
+// keep one to read back after boxing the other
+#[derive(Clone, Default)]
+pub struct RecordingAlerts {
+    alerts: Arc<Mutex<Vec<Alert>>>,
+}
+
+impl RecordingAlerts {
+    // Oldest first
+    pub fn alerts(&self) -> Vec<Alert> {
+        self.alerts.lock().map(|alerts| alerts.clone()).unwrap_or_default()
+    }
+}
+
+impl AlertSink for RecordingAlerts {
+    fn alert(&self, _audio: &Audio, alert: Alert) {
+        if let Ok(mut alerts) = self.alerts.lock() {
+            alerts.push(alert);
+        }
+    }
+}
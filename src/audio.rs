@@ -0,0 +1,126 @@
+use crate::error::{Error, Result};
+use crate::log;
+use crate::tone::{self, Tick};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How sounds reach the speakers: mpv or PowerShell started per sound, or
+// in-process through rodio with the rodio feature
+#[cfg(not(feature = "rodio"))]
+mod process;
+#[cfg(feature = "rodio")]
+mod sink;
+
+#[cfg(not(feature = "rodio"))]
+use process::Backend;
+#[cfg(feature = "rodio")]
+use sink::Backend;
+
+// Plays completion sounds without holding up the UI. If a file can't be
+// played (say a corrupt or unsupported one) audio is switched off for the
+// rest of the run instead of failing again on every sound
+pub struct Audio {
+    disabled: Arc<AtomicBool>,
+    hint_shown: bool,
+    backend: Backend,
+}
+
+impl Audio {
+    pub fn new() -> Self {
+        Self {
+            disabled: Arc::new(AtomicBool::new(false)),
+            hint_shown: false,
+            backend: Backend::new(),
+        }
+    }
+
+    // A track removed since the playlist was loaded is skipped silently.
+    // Volume runs from 0.0 to 1.0
+    pub fn play(&self, path: PathBuf, volume: f32) {
+        if self.disabled.load(Ordering::Relaxed) || !path.is_file() {
+            return;
+        }
+        log::debug(&format!("🔊 Playing sound: {}", path.display()));
+        self.backend.play(path, volume, &self.disabled);
+    }
+
+    // Generated tone for the final countdown
+    pub fn play_tick(&self, tick: Tick, volume: f32) {
+        if self.disabled.load(Ordering::Relaxed) {
+            return;
+        }
+        match tone_file(tick) {
+            Ok(path) => self.backend.tick(path, volume, &self.disabled),
+            Err(e) => log::debug(&format!("✗ {}", e)),
+        }
+    }
+
+    // The configured tick sound, in place of the generated tone. Ticks are
+    // dropped while the previous one is still playing, so they never
+    // overlap or pile up behind a stalled redraw
+    pub fn play_tick_sound(&self, path: PathBuf, volume: f32) {
+        if self.disabled.load(Ordering::Relaxed) {
+            return;
+        }
+        self.backend.tick(path, volume, &self.disabled);
+    }
+
+    // Some(hint) exactly once, after playback has been disabled
+    pub fn take_disabled_hint(&mut self) -> Option<&'static str> {
+        if self.hint_shown || !self.disabled.load(Ordering::Relaxed) {
+            return None;
+        }
+        self.hint_shown = true;
+        Some("Audio disabled: the sound could not be played")
+    }
+
+    // Lets the completion sound play out, for at most `limit`, then stops
+    // whatever is left. For exiting right after the sound was started
+    pub fn finish(&self, limit: Duration) {
+        let deadline = Instant::now() + limit;
+        while Instant::now() < deadline && self.backend.playing(&self.disabled) {
+            thread::sleep(Duration::from_millis(50));
+        }
+        self.stop();
+    }
+
+    // Cuts the completion sound short, e.g. once the prompt is answered
+    pub fn stop(&self) {
+        self.backend.stop();
+    }
+}
+
+impl Default for Audio {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Tones are rendered once per pitch and beep count into the temp dir
+fn tone_file(tick: Tick) -> Result<PathBuf> {
+    let path = env::temp_dir().join(format!("pomodoro-tick-{:.0}-{}.wav", tick.freq, tick.beeps));
+    if !path.exists() {
+        fs::write(&path, tone::render_wav(tick))
+            .map_err(|e| Error::Audio(format!("could not write {}: {}", path.display(), e)))?;
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_hint_shows_once() {
+        let mut audio = Audio::new();
+        assert_eq!(audio.take_disabled_hint(), None);
+        audio.disabled.store(true, Ordering::Relaxed);
+        assert!(audio.take_disabled_hint().is_some());
+        assert_eq!(audio.take_disabled_hint(), None);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::error::{Error, Result};
+use crate::log;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(target_os = "windows")]
+const PLAYER: &str = "powershell";
+#[cfg(not(target_os = "windows"))]
+const PLAYER: &str = "mpv";
+
+// One player process per sound, started off the UI thread
+pub struct Backend {
+    // The program sounds are played with
+    program: &'static str,
+    // A countdown tick is still playing
+    ticking: Arc<AtomicBool>,
+    // Players started for completion sounds, so `stop` ends ours and no one
+    // else's, and none outlive the app
+    players: Arc<Mutex<Vec<Child>>>,
+    // Completion sounds whose player is still being started
+    starting: Arc<AtomicUsize>,
+}
+
+impl Backend {
+    pub fn new() -> Self {
+        Self {
+            program: PLAYER,
+            ticking: Arc::new(AtomicBool::new(false)),
+            players: Arc::new(Mutex::new(Vec::new())),
+            starting: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn play(&self, path: PathBuf, volume: f32, disabled: &Arc<AtomicBool>) {
+        let program = self.program;
+        let disabled = Arc::clone(disabled);
+        let players = Arc::clone(&self.players);
+        let starting = Arc::clone(&self.starting);
+        starting.fetch_add(1, Ordering::SeqCst);
+        thread::spawn(move || {
+            let id = match play_file(program, &path, volume) {
+                Ok(player) => {
+                    let id = player.id();
+                    if let Ok(mut players) = players.lock() {
+                        // Finished players are reaped here so they don't linger
+                        reap(&mut players, &disabled);
+                        players.push(player);
+                    }
+                    Some(id)
+                }
+                Err(e) => {
+                    log::debug(&format!("✗ {}", e));
+                    None
+                }
+            };
+            starting.fetch_sub(1, Ordering::SeqCst);
+
+            // Watched until it exits, so a failure turns audio off right away
+            while let Some(id) = id {
+                thread::sleep(Duration::from_millis(50));
+                let Ok(mut players) = players.lock() else {
+                    break;
+                };
+                reap(&mut players, &disabled);
+                if !players.iter().any(|player| player.id() == id) {
+                    break;
+                }
+            }
+        });
+    }
+
+    // Dropped if the previous tick has not finished
+    pub fn tick(&self, path: PathBuf, volume: f32, disabled: &Arc<AtomicBool>) {
+        if self.ticking.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let program = self.program;
+        let disabled = Arc::clone(disabled);
+        let ticking = Arc::clone(&self.ticking);
+        thread::spawn(move || {
+            match play_file_blocking(program, &path, volume) {
+                Ok(status) => check_exit(status, &disabled),
+                Err(e) => log::debug(&format!("✗ {}", e)),
+            }
+            ticking.store(false, Ordering::Relaxed);
+        });
+    }
+
+    // A completion sound is still being started or playing
+    pub fn playing(&self, disabled: &AtomicBool) -> bool {
+        self.starting.load(Ordering::SeqCst) > 0
+            || self.players.lock().is_ok_and(|mut players| {
+                reap(&mut players, disabled);
+                !players.is_empty()
+            })
+    }
+
+    pub fn stop(&self) {
+        let Ok(mut players) = self.players.lock() else {
+            return;
+        };
+        if !players.is_empty() {
+            log::debug("🔇 Stopping sound");
+        }
+        for mut player in players.drain(..) {
+            let _ = player.kill();
+            let _ = player.wait();
+        }
+    }
+}
+
+// Drops players that have exited. The ones `stop` killed are gone from
+// the list before they get here
+fn reap(players: &mut Vec<Child>, disabled: &AtomicBool) {
+    players.retain_mut(|player| match player.try_wait() {
+        Ok(None) => true,
+        Ok(Some(status)) => {
+            check_exit(status, disabled);
+            false
+        }
+        Err(_) => false,
+    });
+}
+
+// A player that exits with an error couldn't decode or play the file
+fn check_exit(status: ExitStatus, disabled: &AtomicBool) {
+    if status.code().is_some_and(|code| code != 0) {
+        log::debug(&format!("⚠ Player exited with {}, sound disabled", status));
+        disabled.store(true, Ordering::Relaxed);
+    }
+}
+
+fn play_file(program: &str, song_path: &Path, volume: f32) -> Result<Child> {
+    let song_path = song_path.display();
+
+    // Player output would draw over the TUI
+    #[cfg(target_os = "windows")]
+    let player = Command::new(program)
+        .args(["-NoProfile", "-c", &media_player_script(&song_path.to_string(), volume)])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+
+    #[cfg(not(target_os = "windows"))]
+    let player = Command::new(program)
+        .args(["--no-video", "--really-quiet"])
+        .arg(volume_arg(volume))
+        .arg(song_path.to_string())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+
+    player
+        .inspect(|_| log::debug("✓ Player started"))
+        .map_err(|e| Error::Audio(format!("could not start player for {}: {}", song_path, e)))
+}
+
+fn play_file_blocking(program: &str, path: &Path, volume: f32) -> Result<ExitStatus> {
+    #[cfg(target_os = "windows")]
+    let status = Command::new(program)
+        .args(["-NoProfile", "-c", &media_player_script(&path.display().to_string(), volume)])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+
+    #[cfg(not(target_os = "windows"))]
+    let status = Command::new(program)
+        .args(["--no-video", "--really-quiet"])
+        .arg(volume_arg(volume))
+        .arg(path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+
+    status.map_err(|e| Error::Audio(format!("could not play {}: {}", path.display(), e)))
+}
+
+// WPF's MediaPlayer, unlike Media.SoundPlayer, has a volume. It opens the
+// file in the background, so the script waits for the length to be known
+// (giving up with exit code 1 on a file it can't open) and sleeps through it
+#[cfg(any(target_os = "windows", test))]
+fn media_player_script(path: &str, volume: f32) -> String {
+    format!(
+        "Add-Type -AssemblyName PresentationCore; \
+         $p = New-Object System.Windows.Media.MediaPlayer; \
+         $p.Volume = {:.2}; \
+         $p.Open([uri]'{}'); \
+         $t = 0; while (-not $p.NaturalDuration.HasTimeSpan) {{ if ($t++ -ge 100) {{ exit 1 }}; Start-Sleep -Milliseconds 50 }}; \
+         $p.Play(); \
+         Start-Sleep -Milliseconds $p.NaturalDuration.TimeSpan.TotalMilliseconds; \
+         $p.Close()",
+        volume,
+        path.replace('\'', "''")
+    )
+}
+
+#[cfg(not(target_os = "windows"))]
+fn volume_arg(volume: f32) -> String {
+    format!("--volume={}", crate::volume_percent(volume))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn windows_player_gets_the_volume() {
+        let script = media_player_script("C:\\Music\\Tom's bell.wav", 0.4);
+        assert!(script.contains("$p.Volume = 0.40;"));
+        assert!(script.contains("[uri]'C:\\Music\\Tom''s bell.wav'"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_file_the_player_fails_on_disables_audio() {
+        let path = env::temp_dir().join(format!("pomodoro-garbage-{}.mp3", std::process::id()));
+        fs::write(&path, b"not a sound file").unwrap();
+        // Stands in for mpv giving up on the file: it exits with status 1
+        let backend = Backend { program: "false", ..Backend::new() };
+        let disabled = Arc::new(AtomicBool::new(false));
+        backend.play(path.clone(), 1.0, &disabled);
+        for _ in 0..100 {
+            if !backend.playing(&disabled) {
+                break;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        let _ = fs::remove_file(path);
+        assert!(disabled.load(Ordering::Relaxed));
+    }
+}
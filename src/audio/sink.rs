@@ -0,0 +1,121 @@
+use crate::log;
+use rodio::{Decoder, DeviceSinkBuilder, MixerDeviceSink, Player};
+use std::cell::{OnceCell, RefCell};
+use std::fs::File;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// Decoded and mixed in-process, one Player per sound on a shared output
+pub struct Backend {
+    // Opened with the first sound, so a silent run never touches the
+    // device; None if there is no device to open
+    output: OnceCell<Option<MixerDeviceSink>>,
+    sounds: RefCell<Vec<Player>>,
+    tick: RefCell<Option<Player>>,
+}
+
+impl Backend {
+    pub fn new() -> Self {
+        Self {
+            output: OnceCell::new(),
+            sounds: RefCell::new(Vec::new()),
+            tick: RefCell::new(None),
+        }
+    }
+
+    pub fn play(&self, path: PathBuf, volume: f32, disabled: &Arc<AtomicBool>) {
+        if let Some(player) = self.start(&path, volume, disabled) {
+            let mut sounds = self.sounds.borrow_mut();
+            // Finished sounds are dropped here so they don't linger
+            sounds.retain(|sound| !sound.empty());
+            sounds.push(player);
+        }
+    }
+
+    // Dropped if the previous tick has not finished
+    pub fn tick(&self, path: PathBuf, volume: f32, disabled: &Arc<AtomicBool>) {
+        if self.tick.borrow().as_ref().is_some_and(|tick| !tick.empty()) {
+            return;
+        }
+        *self.tick.borrow_mut() = self.start(&path, volume, disabled);
+    }
+
+    pub fn playing(&self, _disabled: &AtomicBool) -> bool {
+        self.sounds.borrow().iter().any(|sound| !sound.empty())
+    }
+
+    pub fn stop(&self) {
+        let mut sounds = self.sounds.borrow_mut();
+        if sounds.iter().any(|sound| !sound.empty()) {
+            log::debug("🔇 Stopping sound");
+        }
+        for sound in sounds.drain(..) {
+            sound.stop();
+        }
+    }
+
+    // A file that can't be decoded, or no device to play it on, switches
+    // audio off
+    fn start(&self, path: &Path, volume: f32, disabled: &AtomicBool) -> Option<Player> {
+        let source = match decode(path) {
+            Ok(source) => source,
+            Err(e) => {
+                log::debug(&format!("⚠ Could not decode {}: {}, sound disabled", path.display(), e));
+                disabled.store(true, Ordering::Relaxed);
+                return None;
+            }
+        };
+        let Some(output) = self.output.get_or_init(open_output) else {
+            disabled.store(true, Ordering::Relaxed);
+            return None;
+        };
+        let player = Player::connect_new(output.mixer());
+        player.set_volume(volume);
+        player.append(source);
+        Some(player)
+    }
+}
+
+// The decoder reads the headers here; a panic on a corrupt one counts as
+// a file that can't be decoded
+fn decode(path: &Path) -> Result<Decoder<std::io::BufReader<File>>, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    panic::catch_unwind(|| Decoder::try_from(file))
+        .map_err(|_| "decoder panicked".to_string())?
+        .map_err(|e| e.to_string())
+}
+
+fn open_output() -> Option<MixerDeviceSink> {
+    match DeviceSinkBuilder::open_default_sink() {
+        Ok(mut output) => {
+            // It would print to the terminal when dropped
+            output.log_on_drop(false);
+            Some(output)
+        }
+        Err(e) => {
+            log::debug(&format!("⚠ No audio output: {}, sound disabled", e));
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn a_file_that_cannot_be_decoded_disables_audio() {
+        let path = env::temp_dir().join(format!("pomodoro-garbage-{}.mp3", std::process::id()));
+        fs::write(&path, b"not a sound file").unwrap();
+        let backend = Backend::new();
+        let disabled = Arc::new(AtomicBool::new(false));
+        backend.play(path.clone(), 1.0, &disabled);
+        let _ = fs::remove_file(path);
+        assert!(disabled.load(Ordering::Relaxed));
+        assert!(!backend.playing(&disabled));
+    }
+}
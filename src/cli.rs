@@ -0,0 +1,220 @@
+use pomodoro_timer::config::Limits;
+use std::iter::Peekable;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: pomodoro-timer [OPTIONS]
+
+Options:
+  --work MINUTES                Work session length
+  --short-break MINUTES         Short break length
+  --long-break MINUTES          Long break length
+  --sessions COUNT              Number of work sessions
+                                (all four skip the setup screen)
+  --stats                       Print history statistics and exit
+  export --csv PATH             Write the history to a CSV file and exit
+  --status                      Print one status line for tmux or waybar and exit
+  --review                      Browse past sessions one at a time
+  --edit-last                   Edit tag, rating and interruptions of the last session
+  --reset-history               Archive the history file and start fresh
+  --clear-history               Delete the history file (asks first)
+  -y, --yes                     Don't ask for confirmation
+  --a11y                        Plain-text output for screen readers
+  --test-notify [TITLE [BODY]]  Send one test notification and exit
+  --once                        One work session, no breaks, then exit
+  --resume                      Continue the timer left by a quit or crash
+  --no-tick                     No countdown ticks this run
+  --emit-events PATH            Append one JSON line per timer event to PATH
+  --serve ADDR                  HTTP control on a localhost address, e.g. 127.0.0.1:7878
+  --debug PATH                  Append timestamped diagnostics to PATH
+  -h, --help                    Show this help";
+
+#[derive(Debug, Default)]
+pub struct Cli {
+    // Print usage and exit
+    pub help: bool,
+    // Print history statistics and exit
+    pub stats: bool,
+    // Write the history as CSV to this file and exit
+    pub export_csv: Option<PathBuf>,
+    // Print the running timer's status line and exit
+    pub status: bool,
+    // Rename the history file aside and start fresh
+    pub reset_history: bool,
+    // Delete the history file
+    pub clear_history: bool,
+    // Skip confirmation prompts
+    pub yes: bool,
+    // Screen-reader friendly plain text output
+    pub a11y: bool,
+    // Edit tag, rating and interruptions of the latest history record
+    pub edit_last: bool,
+    // Browse past sessions one at a time
+    pub review: bool,
+    // Send one notification with this title and body, then exit
+    pub test_notify: Option<(String, String)>,
+    // Override the config's setup defaults; with all four the timer starts
+    // without the setup screen
+    pub work: Option<u32>,
+    pub short_break: Option<u32>,
+    pub long_break: Option<u32>,
+    pub sessions: Option<u32>,
+    // A single work session straight away, ending the run without a break
+    pub once: bool,
+    // Pick up the session snapshot instead of starting on setup
+    pub resume: bool,
+    // Silence the final countdown ticks, whatever the config says
+    pub no_tick: bool,
+    // JSON-lines event stream for other tools
+    pub emit_events: Option<PathBuf>,
+    // Localhost address for the HTTP control server
+    pub serve: Option<SocketAddr>,
+    // Diagnostics log; without it they are dropped
+    pub debug: Option<PathBuf>,
+    // Run a short cycle without a terminal and check the outcome, for CI.
+    // Left out of USAGE
+    pub selftest: bool,
+}
+
+// Anything unrecognized is an error, so a typo like `--wrok` isn't ignored
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Cli, String> {
+    let mut cli = Cli::default();
+    let mut args = args.into_iter().peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => cli.help = true,
+            "--stats" => cli.stats = true,
+            "--status" => cli.status = true,
+            "--reset-history" => cli.reset_history = true,
+            "--clear-history" => cli.clear_history = true,
+            "--yes" | "-y" => cli.yes = true,
+            "--a11y" => cli.a11y = true,
+            "--edit-last" => cli.edit_last = true,
+            "--review" => cli.review = true,
+            "--selftest" => cli.selftest = true,
+            "--once" => cli.once = true,
+            "--resume" => cli.resume = true,
+            "--no-tick" => cli.no_tick = true,
+            "export" => {
+                if args.next().as_deref() != Some("--csv") {
+                    return Err("export needs --csv PATH".to_string());
+                }
+                let path = args.next().ok_or("--csv needs a path")?;
+                cli.export_csv = Some(PathBuf::from(path));
+            }
+            "--emit-events" => {
+                let path = args.next().ok_or("--emit-events needs a path")?;
+                cli.emit_events = Some(PathBuf::from(path));
+            }
+            "--serve" => {
+                let addr = args.next().ok_or("--serve needs an address")?;
+                cli.serve = Some(addr.parse().map_err(|_| format!("--serve: '{}' is not an address like 127.0.0.1:7878", addr))?);
+            }
+            "--debug" => {
+                let path = args.next().ok_or("--debug needs a path")?;
+                cli.debug = Some(PathBuf::from(path));
+            }
+            "--work" => cli.work = Some(positive_value(&arg, args.next())?),
+            "--short-break" => cli.short_break = Some(positive_value(&arg, args.next())?),
+            "--long-break" => cli.long_break = Some(positive_value(&arg, args.next())?),
+            "--sessions" => cli.sessions = Some(positive_value(&arg, args.next())?),
+            "--test-notify" => {
+                let title = optional_value(&mut args).unwrap_or_else(|| "Pomodoro".to_string());
+                let body = optional_value(&mut args).unwrap_or_else(|| "Test notification".to_string());
+                cli.test_notify = Some((title, body));
+            }
+            _ => return Err(format!("unknown argument: {}", arg)),
+        }
+    }
+    Ok(cli)
+}
+
+impl Cli {
+    // Work, short break, long break and session count, if all were given
+    pub fn durations(&self) -> Option<[u32; 4]> {
+        Some([self.work?, self.short_break?, self.long_break?, self.sessions?])
+    }
+
+    // The config's setup limits hold for the flags too
+    pub fn check_limits(&self, limits: &Limits) -> Result<(), String> {
+        let flags = [
+            ("--work", self.work, limits.work),
+            ("--short-break", self.short_break, limits.short_break),
+            ("--long-break", self.long_break, limits.long_break),
+            ("--sessions", self.sessions, limits.sessions),
+        ];
+        for (flag, value, (min, max)) in flags {
+            match value {
+                Some(n) if n < min || n > max => {
+                    return Err(format!("invalid value for {}: '{}' (must be {}–{}, see [limits] in the config)", flag, n, min, max))
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn positive_value(flag: &str, value: Option<String>) -> Result<u32, String> {
+    let value = value.ok_or_else(|| format!("{} needs a value", flag))?;
+    match value.parse::<u32>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("invalid value for {}: '{}' (expected a whole number above 0)", flag, value)),
+    }
+}
+
+// Optional flag values stop at the next flag
+fn optional_value<I: Iterator<Item = String>>(args: &mut Peekable<I>) -> Option<String> {
+    args.next_if(|arg| !arg.starts_with('-'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn unknown_argument_is_an_error() {
+        assert_eq!(parse(&["--stats", "--wrok"]).unwrap_err(), "unknown argument: --wrok");
+    }
+
+    #[test]
+    fn export_takes_a_csv_path() {
+        assert_eq!(parse(&["export", "--csv", "out.csv"]).unwrap().export_csv, Some(PathBuf::from("out.csv")));
+        assert!(parse(&["export"]).is_err());
+        assert!(parse(&["export", "--csv"]).is_err());
+    }
+
+    #[test]
+    fn test_notify_values_are_optional() {
+        let cli = parse(&["--test-notify", "--yes"]).unwrap();
+        assert_eq!(cli.test_notify, Some(("Pomodoro".to_string(), "Test notification".to_string())));
+        assert!(cli.yes);
+
+        let cli = parse(&["--test-notify", "Hi", "there"]).unwrap();
+        assert_eq!(cli.test_notify, Some(("Hi".to_string(), "there".to_string())));
+    }
+
+    #[test]
+    fn durations_need_positive_numbers() {
+        let cli = parse(&["--work", "50", "--short-break", "10", "--long-break", "20", "--sessions", "3"]).unwrap();
+        assert_eq!(cli.durations(), Some([50, 10, 20, 3]));
+        assert_eq!(parse(&["--work", "50"]).unwrap().durations(), None);
+
+        assert!(parse(&["--work", "0"]).unwrap_err().contains("--work"));
+        assert!(parse(&["--sessions", "many"]).unwrap_err().contains("'many'"));
+        assert_eq!(parse(&["--long-break"]).unwrap_err(), "--long-break needs a value");
+    }
+
+    #[test]
+    fn durations_stay_within_the_limits() {
+        let limits = Limits::default();
+        assert!(parse(&["--work", "50", "--sessions", "3"]).unwrap().check_limits(&limits).is_ok());
+        assert!(parse(&["--work", "500"]).unwrap().check_limits(&limits).unwrap_err().contains("--work: '500'"));
+        assert!(parse(&["--sessions", "999"]).unwrap().check_limits(&limits).unwrap_err().contains("must be 1–20"));
+    }
+}
@@ -0,0 +1,51 @@
+use crate::log;
+use chrono::{DateTime, Local};
+
+// Wall-clock reads that never run backwards. The countdown itself runs on
+// `Instant`, which is monotonic; this only guards timestamps we record, so
+// an NTP correction can't make a later session look older than an earlier one
+#[derive(Default)]
+pub struct WallClock {
+    last: Option<DateTime<Local>>,
+}
+
+impl WallClock {
+    pub fn now(&mut self) -> DateTime<Local> {
+        self.observe(Local::now())
+    }
+
+    fn observe(&mut self, now: DateTime<Local>) -> DateTime<Local> {
+        match self.last {
+            Some(last) if now < last => {
+                log::debug(&format!(
+                    "⚠ System clock went back {}s, keeping timestamps monotonic",
+                    (last - now).num_seconds()
+                ));
+                last
+            }
+            _ => {
+                self.last = Some(now);
+                now
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    #[test]
+    fn backward_jump_keeps_last_time() {
+        let mut clock = WallClock::default();
+        let start = Local::now();
+        assert_eq!(clock.observe(start), start);
+
+        let jumped = start - TimeDelta::minutes(10);
+        assert_eq!(clock.observe(jumped), start);
+
+        let later = start + TimeDelta::seconds(5);
+        assert_eq!(clock.observe(later), later);
+    }
+}
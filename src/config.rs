@@ -0,0 +1,317 @@
+use crate::error::{Error, Result};
+use crate::keymap::KeyBinding;
+use crate::tone::CountdownPattern;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    // Setup screen defaults, used when a field is left blank
+    pub work_minutes: u32,
+    pub short_break_minutes: u32,
+    pub long_break_minutes: u32,
+    pub total_sessions: u32,
+    // Long break after every this many work sessions, 0 for never
+    pub long_break_interval: u32,
+    // Completion sound: an audio file, a directory or an .m3u playlist
+    pub sound_path: Option<String>,
+    // Fixed seed for picking tracks, for reproducible runs
+    pub sound_seed: Option<u64>,
+    // Short click played on key confirmations (off when unset)
+    pub ui_sound: Option<String>,
+    // Played once a second through the final countdown instead of the
+    // generated tones
+    pub tick_sound: Option<String>,
+    pub muted: bool,
+    // Completion sound volume, 0.0 to 1.0
+    pub volume: f32,
+    // Vibrate (or ring the terminal bell) when a session completes
+    pub vibrate: bool,
+    // Helper command that triggers the vibration, e.g. `termux-vibrate`
+    pub vibrate_command: Option<String>,
+    // Markdown checklist or todo.txt to pick the focus task from
+    pub todo_file: Option<String>,
+    // ASCII art shown on the break prompt instead of the title
+    pub celebration_art: Option<String>,
+    // Action name -> key(s), e.g. `skip = ["s", "enter"]`
+    pub keys: HashMap<String, KeyBinding>,
+    // Strict focus: show only "FOCUS" during work, reveal time on a key press
+    pub hide_clock: bool,
+    pub reveal_seconds: u64,
+    pub limits: Limits,
+    // Lowest remaining time the -1m key can bring each timer down to
+    pub min_remaining: MinRemaining,
+    // Seconds before a pause resumes on its own, 0 disables
+    pub auto_resume_after: u64,
+    // Pause a work session after this many seconds without a key press,
+    // 0 disables
+    pub auto_pause_idle_secs: u64,
+    // "Run 42% complete" across all sessions and breaks
+    pub show_run_progress: bool,
+    // The current session's dot fills up as the work session runs
+    pub session_dots: bool,
+    // Blink the clock's colon once a second while it counts
+    pub blink_colon: bool,
+    // Big clock glyphs: "block", "thin" or "outline"
+    pub font: String,
+    // Widest the centered content gets, 0 for the full terminal width
+    pub max_content_width: u16,
+    // Beside a width-limited timer: progress on the left, next up and
+    // session stats on the right
+    pub side_panels: bool,
+    // Progress bar filled in eighths of a cell from the exact time left,
+    // rather than in whole cells
+    pub smooth_progress: bool,
+    // Built-in color theme: "default", "solarized" or "mono"
+    pub theme: String,
+    // Theme part -> "#rrggbb", "#rgb" or a color name, over the theme's own
+    pub colors: HashMap<String, String>,
+    // Screen -> help line replacing the generated one ("setup", "running",
+    // "break_prompt", "break_leadin", "work_prompt", "ritual", "focus", "stats",
+    // "overtime")
+    pub help_overrides: HashMap<String, String>,
+    // Work session title; {current}, {total}, {remaining} and {index}
+    // (0-based) are filled in
+    pub session_label_format: String,
+    // Minutes for each successive long break, e.g. [15, 20, 30]; the last
+    // one repeats. Empty uses the setup screen's long break
+    pub long_break_cycle: Vec<u32>,
+    // Ask "Quit? [y/N]" before quitting a running session
+    pub confirm_quit: bool,
+    // Ask for the next action once all sessions are done
+    pub ask_next_action: bool,
+    // Skipped work sessions still count toward the total
+    pub count_skipped_work: bool,
+    // Only work sessions that ran out count toward the long break
+    pub long_break_needs_completed: bool,
+    // Meeting mode length when started with the key or a bare `:defer`
+    pub defer_minutes: u32,
+    // Beep through the last this many seconds of every timer, 0 disables
+    pub countdown_ticks: u64,
+    pub countdown_pattern: CountdownPattern,
+    // Seconds between session state snapshots, 0 disables
+    pub autosave_seconds: u64,
+    // "Break starting..." count after confirming a break, 0 starts at once
+    pub break_leadin_seconds: u64,
+    // .ics file to add each completed work session to
+    pub ical_export_path: Option<String>,
+    // Checklist shown one step at a time before the first work session
+    pub ritual: Vec<String>,
+    // Start breaks without the "Ready to start your break?" prompt
+    pub auto_start_breaks: bool,
+    // Start work after a break without the "ready to focus?" prompt
+    pub auto_start_work: bool,
+    // A finished work session counts up past zero until skipped to the
+    // break, and the extra time goes in the history
+    pub overtime: bool,
+    // Pomodoros to aim for each day, shown as "3/8 today"; 0 = no goal
+    pub daily_goal: u32,
+    // Ask why when a break is skipped, picking from `skip_reasons`
+    pub ask_skip_reason: bool,
+    pub skip_reasons: Vec<String>,
+    // Named sets of the four setup values, picked with ←/→ on setup
+    #[serde(rename = "preset")]
+    pub presets: Vec<Preset>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    pub work_minutes: u32,
+    pub short_break_minutes: u32,
+    pub long_break_minutes: u32,
+    pub total_sessions: u32,
+}
+
+impl Preset {
+    // Setup's first four fields, in order
+    pub fn values(&self) -> [u32; 4] {
+        [self.work_minutes, self.short_break_minutes, self.long_break_minutes, self.total_sessions]
+    }
+}
+
+// Allowed [min, max] for each setup field
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Limits {
+    pub work: (u32, u32),
+    pub short_break: (u32, u32),
+    pub long_break: (u32, u32),
+    pub sessions: (u32, u32),
+    pub long_break_interval: (u32, u32),
+}
+
+// Seconds, per timer type
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct MinRemaining {
+    pub work: u64,
+    pub short_break: u64,
+    pub long_break: u64,
+}
+
+impl Default for MinRemaining {
+    fn default() -> Self {
+        Self {
+            work: 60,
+            short_break: 60,
+            long_break: 60,
+        }
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            work: (1, 180),
+            short_break: (1, 60),
+            long_break: (1, 120),
+            sessions: (1, 20),
+            long_break_interval: (0, 20),
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            work_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            total_sessions: 4,
+            long_break_interval: 4,
+            sound_path: None,
+            sound_seed: None,
+            ui_sound: None,
+            tick_sound: None,
+            muted: false,
+            volume: 1.0,
+            vibrate: false,
+            vibrate_command: None,
+            todo_file: None,
+            celebration_art: None,
+            keys: HashMap::new(),
+            hide_clock: false,
+            reveal_seconds: 5,
+            limits: Limits::default(),
+            min_remaining: MinRemaining::default(),
+            auto_resume_after: 0,
+            auto_pause_idle_secs: 0,
+            show_run_progress: false,
+            session_dots: false,
+            blink_colon: false,
+            font: "block".to_string(),
+            max_content_width: 0,
+            side_panels: false,
+            smooth_progress: false,
+            theme: "default".to_string(),
+            colors: HashMap::new(),
+            help_overrides: HashMap::new(),
+            session_label_format: "WORK SESSION {current}/{total}".to_string(),
+            long_break_cycle: Vec::new(),
+            confirm_quit: true,
+            ask_next_action: false,
+            count_skipped_work: true,
+            long_break_needs_completed: false,
+            defer_minutes: 30,
+            countdown_ticks: 0,
+            countdown_pattern: CountdownPattern::Single,
+            autosave_seconds: 30,
+            break_leadin_seconds: 0,
+            ical_export_path: None,
+            ritual: Vec::new(),
+            auto_start_breaks: false,
+            auto_start_work: true,
+            overtime: false,
+            daily_goal: 0,
+            ask_skip_reason: false,
+            skip_reasons: ["in flow", "meeting", "leaving"].map(String::from).to_vec(),
+            presets: Vec::new(),
+        }
+    }
+}
+
+// ~/.config/pomodoro/config.toml, honouring $XDG_CONFIG_HOME
+pub fn config_path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("pomodoro").join("config.toml"))
+}
+
+// Updates a single top-level key, keeping the rest of the file (comments
+// and formatting included) as the user wrote it
+pub fn save_setting(key: &str, value: impl Into<toml_edit::Value>) -> Result<()> {
+    let path = config_path().ok_or_else(|| Error::Config("no home directory".to_string()))?;
+    let text = fs::read_to_string(&path).unwrap_or_default();
+    let mut doc: toml_edit::DocumentMut = text
+        .parse()
+        .map_err(|e| Error::Config(format!("{}: {}", path.display(), e)))?;
+    doc[key] = toml_edit::value(value);
+
+    let write_error = |e: io::Error| Error::Config(format!("{}: {}", path.display(), e));
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(write_error)?;
+    }
+    fs::write(&path, doc.to_string()).map_err(write_error)
+}
+
+// Missing or malformed config falls back to defaults
+pub fn load_config() -> Config {
+    read_config().unwrap_or_default()
+}
+
+// Like load_config, but a malformed file is an error rather than defaults,
+// so a half-saved edit doesn't reset a running timer's settings
+pub fn read_config() -> Result<Config> {
+    let Some(text) = config_path().and_then(|path| fs::read_to_string(path).ok()) else {
+        return Ok(Config::default());
+    };
+    toml::from_str(&text).map_err(|e| Error::Config(e.message().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_config_parses() {
+        let config: Config = toml::from_str(
+            r#"
+            work_minutes = 50
+            short_break_minutes = 10
+            sound_path = "/home/you/Music/bell.mp3"
+
+            [limits]
+            work = [5, 90]
+
+            [[preset]]
+            name = "Deep Work"
+            work_minutes = 50
+            short_break_minutes = 10
+            long_break_minutes = 30
+            total_sessions = 3
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.work_minutes, 50);
+        assert_eq!(config.presets[0].values(), [50, 10, 30, 3]);
+        assert_eq!(config.short_break_minutes, 10);
+        assert_eq!(config.sound_path.as_deref(), Some("/home/you/Music/bell.mp3"));
+        assert_eq!(config.limits.work, (5, 90));
+        // Unset keys keep their defaults
+        assert_eq!(config.long_break_minutes, 15);
+        assert_eq!(config.total_sessions, 4);
+        assert_eq!(config.limits.sessions, (1, 20));
+    }
+
+    #[test]
+    fn malformed_config_is_an_error() {
+        assert!(toml::from_str::<Config>("work_minutes = \"lots\"").is_err());
+    }
+}
@@ -0,0 +1,29 @@
+use std::io;
+use thiserror::Error;
+
+// Failures grouped by where they come from, so they can be reported (and
+// exit codes picked) per kind instead of as one opaque io::Error
+#[derive(Debug, Error)]
+pub enum Error {
+    // Bad command-line arguments
+    #[error("{0}")]
+    Usage(String),
+    // Reading or writing the config file
+    #[error("config error: {0}")]
+    Config(String),
+    // Drawing, reading keys, or prompting on the terminal
+    #[error("terminal error: {0}")]
+    Terminal(#[from] io::Error),
+    // Loading, writing, archiving or clearing session history
+    #[error("history error: {0}")]
+    History(#[source] io::Error),
+    #[error("audio error: {0}")]
+    Audio(String),
+    #[error("notification error: {0}")]
+    Notification(String),
+    // A --selftest check that didn't hold, by name
+    #[error("self-test failed: {0}")]
+    SelfTest(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
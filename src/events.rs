@@ -0,0 +1,204 @@
+use crate::{AppState, PomodoroApp, TimerType};
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    SessionStart,
+    SessionEnd,
+    BreakStart,
+    BreakEnd,
+    Pause,
+    Resume,
+    // The last session ended the run
+    RunComplete,
+    Quit,
+}
+
+// One line of the --emit-events stream, e.g.
+// {"event":"session_start","timestamp":"2024-05-01T09:00:00+02:00","timer_type":"work","session":1,"total_sessions":4,"remaining_secs":1500}
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct Event {
+    pub event: EventKind,
+    pub timestamp: DateTime<Local>,
+    // The timer the event is about; for quit and run_complete, the last one
+    pub timer_type: TimerType,
+    pub session: u32,
+    pub total_sessions: u32,
+    pub remaining_secs: u64,
+    // Whether a work session ran out rather than being skipped or quit;
+    // only on session_end
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed: Option<bool>,
+}
+
+// What the stream compares between passes of the event loop
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Phase {
+    // None outside a countdown: setup, prompts, stats
+    pub timer: Option<TimerType>,
+    pub last_timer: TimerType,
+    pub session: u32,
+    pub total_sessions: u32,
+    pub paused: bool,
+    pub remaining_secs: u64,
+    pub completed_work: u32,
+}
+
+impl Phase {
+    pub fn of(app: &PomodoroApp) -> Self {
+        let counting = matches!(app.state, AppState::Running | AppState::Overtime);
+        Self {
+            timer: counting.then_some(app.timer_type),
+            last_timer: app.timer_type,
+            session: app.current_session,
+            total_sessions: app.total_sessions,
+            paused: app.paused,
+            remaining_secs: app.secs_left(),
+            completed_work: app.completed_work_sessions,
+        }
+    }
+
+    fn event(&self, event: EventKind, now: DateTime<Local>) -> Event {
+        Event {
+            event,
+            timestamp: now,
+            timer_type: self.timer.unwrap_or(self.last_timer),
+            session: self.session,
+            total_sessions: self.total_sessions,
+            remaining_secs: self.remaining_secs,
+            completed: None,
+        }
+    }
+
+    fn end(&self, completed: bool, now: DateTime<Local>) -> Option<Event> {
+        Some(match self.timer? {
+            TimerType::Work => Event { completed: Some(completed), ..self.event(EventKind::SessionEnd, now) },
+            _ => self.event(EventKind::BreakEnd, now),
+        })
+    }
+}
+
+// Events for whatever happened between two passes. A timer that changed
+// type or session number ended and the next one started
+pub fn transitions(prev: &Phase, cur: &Phase, now: DateTime<Local>) -> Vec<Event> {
+    let mut events = Vec::new();
+    if (prev.timer, prev.session) != (cur.timer, cur.session) {
+        events.extend(prev.end(cur.completed_work > prev.completed_work, now));
+        match cur.timer {
+            Some(TimerType::Work) => events.push(cur.event(EventKind::SessionStart, now)),
+            Some(_) => events.push(cur.event(EventKind::BreakStart, now)),
+            None => {}
+        }
+    } else if cur.timer.is_some() && prev.paused != cur.paused {
+        events.push(cur.event(if cur.paused { EventKind::Pause } else { EventKind::Resume }, now));
+    }
+    events
+}
+
+// JSON lines appended to a file (or a pipe, e.g. /dev/fd/3), since the TUI
+// owns stdout. Write errors are dropped; the timer goes on without them
+pub struct EventStream {
+    file: File,
+    last: Option<Phase>,
+}
+
+impl EventStream {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { file, last: None })
+    }
+
+    // Called on every pass of the event loop
+    pub fn observe(&mut self, app: &PomodoroApp) {
+        let cur = Phase::of(app);
+        let prev = self.last.replace(cur).unwrap_or(Phase { timer: None, ..cur });
+        for event in transitions(&prev, &cur, Local::now()) {
+            self.write(&event);
+        }
+    }
+
+    // The timer still counting ends with the run
+    pub fn close(mut self, app: &PomodoroApp, finished: bool) {
+        self.observe(app);
+        let phase = Phase::of(app);
+        let now = Local::now();
+        if let Some(event) = phase.end(finished, now) {
+            self.write(&event);
+        }
+        self.write(&phase.event(if finished { EventKind::RunComplete } else { EventKind::Quit }, now));
+    }
+
+    fn write(&mut self, event: &Event) {
+        if let Ok(line) = serde_json::to_string(event) {
+            let _ = writeln!(self.file, "{}", line);
+            let _ = self.file.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn phase(timer: Option<TimerType>, session: u32) -> Phase {
+        Phase {
+            timer,
+            last_timer: timer.unwrap_or(TimerType::Work),
+            session,
+            total_sessions: 4,
+            paused: false,
+            remaining_secs: 0,
+            completed_work: 0,
+        }
+    }
+
+    fn kinds(prev: &Phase, cur: &Phase) -> Vec<EventKind> {
+        let now = Local.with_ymd_and_hms(2024, 5, 1, 9, 0, 0).unwrap();
+        transitions(prev, cur, now).into_iter().map(|event| event.event).collect()
+    }
+
+    #[test]
+    fn timer_changes_end_one_and_start_the_next() {
+        let setup = phase(None, 1);
+        let work = phase(Some(TimerType::Work), 1);
+        let prompt = Phase { completed_work: 1, ..phase(None, 1) };
+        assert_eq!(kinds(&setup, &work), [EventKind::SessionStart]);
+        assert_eq!(kinds(&work, &work), []);
+        assert_eq!(kinds(&work, &prompt), [EventKind::SessionEnd]);
+        assert_eq!(kinds(&prompt, &phase(Some(TimerType::ShortBreak), 1)), [EventKind::BreakStart]);
+        assert_eq!(
+            kinds(&phase(Some(TimerType::ShortBreak), 1), &phase(Some(TimerType::Work), 2)),
+            [EventKind::BreakEnd, EventKind::SessionStart]
+        );
+
+        let now = Local::now();
+        assert_eq!(transitions(&work, &prompt, now)[0].completed, Some(true));
+        assert_eq!(transitions(&work, &setup, now)[0].completed, Some(false));
+    }
+
+    #[test]
+    fn pausing_is_only_reported_while_counting() {
+        let work = phase(Some(TimerType::Work), 1);
+        let paused = Phase { paused: true, ..work };
+        assert_eq!(kinds(&work, &paused), [EventKind::Pause]);
+        assert_eq!(kinds(&paused, &work), [EventKind::Resume]);
+        assert_eq!(kinds(&phase(None, 1), &Phase { paused: true, ..phase(None, 1) }), []);
+    }
+
+    #[test]
+    fn events_serialize_as_flat_json() {
+        let now = Local.with_ymd_and_hms(2024, 5, 1, 9, 0, 0).unwrap();
+        let event = Phase { remaining_secs: 1500, ..phase(Some(TimerType::Work), 1) }.event(EventKind::SessionStart, now);
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "session_start");
+        assert_eq!(json["timer_type"], "work");
+        assert_eq!(json["remaining_secs"], 1500);
+        assert!(json.get("completed").is_none());
+    }
+}
@@ -0,0 +1,88 @@
+use crate::history::HistoryEntry;
+use chrono::Duration;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const CSV_HEADER: &str = "date,start_time,type,planned_minutes,actual_minutes,task_label";
+
+// One row per history record, oldest first, in local time. Every record is
+// a work session; skipped ones say so in `type` and leave actual_minutes
+// blank, since how far they got isn't recorded
+pub fn to_csv(records: &[HistoryEntry]) -> String {
+    let mut csv = format!("{}\n", CSV_HEADER);
+    for record in records {
+        // The timestamp is the end; pauses and overtime pushed it back
+        let wall_secs = record.duration_secs + record.paused_secs + record.overtime_secs;
+        let start = record.timestamp - Duration::seconds(wall_secs as i64);
+        let actual = if record.skipped {
+            String::new()
+        } else {
+            minutes(record.duration_secs + record.overtime_secs)
+        };
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            start.format("%Y-%m-%d"),
+            start.format("%H:%M:%S"),
+            if record.skipped { "skipped" } else { "work" },
+            minutes(record.duration_secs),
+            actual,
+            // The focus task for a session left without a label
+            quote(record.label.as_deref().or(record.task.as_deref()).unwrap_or("")),
+        ));
+    }
+    csv
+}
+
+// An empty history still gets the header row
+pub fn write_csv(path: &Path, records: &[HistoryEntry]) -> io::Result<()> {
+    fs::write(path, to_csv(records))
+}
+
+fn minutes(secs: u64) -> String {
+    format!("{:.1}", secs as f64 / 60.0)
+}
+
+// RFC 4180: fields with commas, quotes or line breaks go in double quotes,
+// with any quotes inside doubled
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    fn entry(hour: u32, label: Option<&str>) -> HistoryEntry {
+        HistoryEntry {
+            label: label.map(String::from),
+            ..HistoryEntry::at(Local.with_ymd_and_hms(2025, 1, 14, hour, 30, 0).unwrap())
+        }
+    }
+
+    #[test]
+    fn history_becomes_quoted_csv() {
+        let plain = entry(9, Some("reading"));
+        let overtime = HistoryEntry { paused_secs: 60, overtime_secs: 90, ..entry(10, Some("client A, \"urgent\"")) };
+        let skipped = HistoryEntry { skipped: true, ..entry(11, None) };
+        let task_only = HistoryEntry { task: Some("Write report".to_string()), ..entry(12, None) };
+        assert_eq!(
+            to_csv(&[plain, overtime, skipped, task_only]),
+            "date,start_time,type,planned_minutes,actual_minutes,task_label\n\
+             2025-01-14,09:05:00,work,25.0,25.0,reading\n\
+             2025-01-14,10:02:30,work,25.0,26.5,\"client A, \"\"urgent\"\"\"\n\
+             2025-01-14,11:05:00,skipped,25.0,,\n\
+             2025-01-14,12:05:00,work,25.0,25.0,Write report\n"
+        );
+    }
+
+    #[test]
+    fn empty_history_still_has_a_header() {
+        assert_eq!(to_csv(&[]), format!("{}\n", CSV_HEADER));
+    }
+}
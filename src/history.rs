@@ -0,0 +1,155 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+#[cfg(not(feature = "sqlite"))]
+mod json;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+#[cfg(not(feature = "sqlite"))]
+pub use json::{append_record, load_records, replace_last_record};
+#[cfg(feature = "sqlite")]
+pub use sqlite::{append_record, load_records, replace_last_record};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    // When the work session finished
+    pub timestamp: DateTime<Local>,
+    pub duration_secs: u64,
+    pub session: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+    // Free text typed on the setup screen or with `l` while running
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub pauses: u32,
+    #[serde(default)]
+    pub paused_secs: u64,
+    #[serde(default)]
+    pub interruptions: u32,
+    // Free-form label added after the fact with --edit-last
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    // Self-assessed focus, 1-5
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+    // Ended early with skip rather than by the timer running out
+    #[serde(default)]
+    pub skipped: bool,
+    // Used up one of the run's sessions; false only for a skip that
+    // `count_skipped_work = false` left out
+    #[serde(default = "counted_default")]
+    pub counted: bool,
+    // Why the break after this session was skipped, when asked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub break_skip_reason: Option<String>,
+    // Worked on past the bell in overtime mode, on top of duration_secs
+    #[serde(default)]
+    pub overtime_secs: u64,
+}
+
+fn counted_default() -> bool {
+    true
+}
+
+// A plain 25-minute first session for test fixtures, e.g.
+// HistoryEntry { skipped: true, ..HistoryEntry::at(timestamp) }
+#[cfg(test)]
+impl HistoryEntry {
+    pub fn at(timestamp: DateTime<Local>) -> Self {
+        HistoryEntry {
+            timestamp,
+            duration_secs: 1500,
+            session: 1,
+            task: None,
+            label: None,
+            pauses: 0,
+            paused_secs: 0,
+            interruptions: 0,
+            tag: None,
+            rating: None,
+            skipped: false,
+            counted: true,
+            break_skip_reason: None,
+            overtime_secs: 0,
+        }
+    }
+}
+
+#[cfg(not(feature = "sqlite"))]
+const FILE_NAME: &str = "history.json";
+#[cfg(feature = "sqlite")]
+const FILE_NAME: &str = "history.db";
+
+// ~/.local/share/pomodoro/history.json (history.db with the sqlite
+// feature), honouring $XDG_DATA_HOME
+pub fn history_path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_DATA_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".local").join("share"),
+    };
+    Some(base.join("pomodoro").join(FILE_NAME))
+}
+
+// Renames the history file aside with a timestamp; None if there was none
+pub fn archive_history() -> io::Result<Option<PathBuf>> {
+    let Some(path) = history_path().filter(|path| path.exists()) else {
+        return Ok(None);
+    };
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("history");
+    let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("json");
+    let stamp = Local::now().format("%Y%m%d-%H%M%S");
+    let archive = path.with_file_name(format!("{}-{}.{}", stem, stamp, ext));
+    if archive.exists() {
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, "archive already exists"));
+    }
+    fs::rename(&path, &archive)?;
+    Ok(Some(archive))
+}
+
+// Deletes the history file; false if there was none
+pub fn clear_history() -> io::Result<bool> {
+    match history_path() {
+        Some(path) if path.exists() => fs::remove_file(path).map(|_| true),
+        _ => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_round_trip() {
+        let records = vec![
+            HistoryEntry {
+                task: Some("Write report".to_string()),
+                label: Some("client A".to_string()),
+                pauses: 2,
+                paused_secs: 90,
+                interruptions: 1,
+                tag: Some("writing".to_string()),
+                rating: Some(4),
+                break_skip_reason: Some("in flow".to_string()),
+                ..HistoryEntry::at(Local::now())
+            },
+            HistoryEntry { session: 2, skipped: true, counted: false, ..HistoryEntry::at(Local::now()) },
+        ];
+        let text = serde_json::to_string(&records).unwrap();
+        assert_eq!(serde_json::from_str::<Vec<HistoryEntry>>(&text).unwrap(), records);
+    }
+
+    #[test]
+    fn early_records_still_load() {
+        // Written before pauses, tags, ratings and skips were tracked
+        let text = r#"[{"timestamp":"2025-01-14T12:25:00+01:00","duration_secs":1500,"session":3}]"#;
+        let records: Vec<HistoryEntry> = serde_json::from_str(text).unwrap();
+        assert_eq!(records[0].session, 3);
+        assert!(records[0].counted && !records[0].skipped);
+        assert_eq!(records[0].task, None);
+    }
+}
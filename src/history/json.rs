@@ -0,0 +1,38 @@
+use super::{history_path, HistoryEntry};
+use std::fs;
+use std::io;
+
+// Missing or corrupt history reads as empty
+pub fn load_records() -> Vec<HistoryEntry> {
+    history_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+pub fn append_record(entry: &HistoryEntry) -> io::Result<()> {
+    let mut records = load_records();
+    records.push(entry.clone());
+    write_records(&records)
+}
+
+pub fn replace_last_record(entry: &HistoryEntry) -> io::Result<()> {
+    let mut records = load_records();
+    let last = records
+        .last_mut()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "history is empty"))?;
+    *last = entry.clone();
+    write_records(&records)
+}
+
+// Write to a temp file and rename so a crash never leaves half a file
+fn write_records(records: &[HistoryEntry]) -> io::Result<()> {
+    let path = history_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(records)?)?;
+    fs::rename(&tmp, &path)
+}
@@ -0,0 +1,219 @@
+use super::{history_path, HistoryEntry};
+use chrono::{DateTime, Local};
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OpenFlags, Row};
+use std::fs;
+use std::io;
+
+// One column per field, so the history can be queried directly, e.g.
+// SELECT date(timestamp, 'localtime'), SUM(duration_secs) FROM sessions
+// WHERE NOT skipped GROUP BY 1. A new field needs an ALTER TABLE in
+// `migrate`
+const COLUMNS: &str = "timestamp, duration_secs, session, task, label, pauses, paused_secs, interruptions, \
+                       tag, rating, skipped, counted, break_skip_reason, overtime_secs";
+
+fn open() -> io::Result<Connection> {
+    let path = history_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let conn = Connection::open(path).map_err(io::Error::other)?;
+    migrate(&conn).map_err(io::Error::other)?;
+    Ok(conn)
+}
+
+fn create_table(conn: &Connection) -> rusqlite::Result<usize> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS sessions (
+            id INTEGER PRIMARY KEY,
+            timestamp TEXT NOT NULL,
+            duration_secs INTEGER NOT NULL,
+            session INTEGER NOT NULL,
+            task TEXT,
+            label TEXT,
+            pauses INTEGER NOT NULL DEFAULT 0,
+            paused_secs INTEGER NOT NULL DEFAULT 0,
+            interruptions INTEGER NOT NULL DEFAULT 0,
+            tag TEXT,
+            rating INTEGER,
+            skipped INTEGER NOT NULL DEFAULT 0,
+            counted INTEGER NOT NULL DEFAULT 1,
+            break_skip_reason TEXT,
+            overtime_secs INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )
+}
+
+// Databases from before the columns kept each record as a JSON blob
+fn migrate(conn: &Connection) -> rusqlite::Result<()> {
+    create_table(conn)?;
+    if !has_record_column(conn)? {
+        return Ok(());
+    }
+    let records = read_blobs(conn)?;
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DROP TABLE sessions", [])?;
+    create_table(&tx)?;
+    for entry in &records {
+        insert(&tx, entry)?;
+    }
+    tx.commit()
+}
+
+fn has_record_column(conn: &Connection) -> rusqlite::Result<bool> {
+    let mut stmt = conn.prepare("SELECT 1 FROM pragma_table_info('sessions') WHERE name = 'record'")?;
+    stmt.exists([])
+}
+
+fn read_blobs(conn: &Connection) -> rusqlite::Result<Vec<HistoryEntry>> {
+    let mut stmt = conn.prepare("SELECT record FROM sessions ORDER BY id")?;
+    let records = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .filter_map(Result::ok)
+        .filter_map(|json| serde_json::from_str(&json).ok())
+        .collect();
+    Ok(records)
+}
+
+fn read(conn: &Connection) -> rusqlite::Result<Vec<HistoryEntry>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM sessions ORDER BY id", COLUMNS))?;
+    let records = stmt.query_map([], entry)?.filter_map(Result::ok).collect();
+    Ok(records)
+}
+
+fn entry(row: &Row) -> rusqlite::Result<HistoryEntry> {
+    Ok(HistoryEntry {
+        timestamp: DateTime::parse_from_rfc3339(&row.get::<_, String>(0)?)
+            .map_err(|err| rusqlite::Error::FromSqlConversionFailure(0, Type::Text, Box::new(err)))?
+            .with_timezone(&Local),
+        duration_secs: row.get(1)?,
+        session: row.get(2)?,
+        task: row.get(3)?,
+        label: row.get(4)?,
+        pauses: row.get(5)?,
+        paused_secs: row.get(6)?,
+        interruptions: row.get(7)?,
+        tag: row.get(8)?,
+        rating: row.get(9)?,
+        skipped: row.get(10)?,
+        counted: row.get(11)?,
+        break_skip_reason: row.get(12)?,
+        overtime_secs: row.get(13)?,
+    })
+}
+
+fn insert(conn: &Connection, entry: &HistoryEntry) -> rusqlite::Result<usize> {
+    conn.execute(
+        &format!("INSERT INTO sessions ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)", COLUMNS),
+        params![
+            entry.timestamp.to_rfc3339(),
+            entry.duration_secs,
+            entry.session,
+            entry.task,
+            entry.label,
+            entry.pauses,
+            entry.paused_secs,
+            entry.interruptions,
+            entry.tag,
+            entry.rating,
+            entry.skipped,
+            entry.counted,
+            entry.break_skip_reason,
+            entry.overtime_secs,
+        ],
+    )
+}
+
+// Missing or unreadable history reads as empty. Opened read-only, so
+// looking at the history never creates it; a database still in the
+// JSON-blob layout is read as it is and converted on the next write
+pub fn load_records() -> Vec<HistoryEntry> {
+    let Some(path) = history_path().filter(|path| path.exists()) else {
+        return Vec::new();
+    };
+    let Ok(conn) = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY) else {
+        return Vec::new();
+    };
+    match has_record_column(&conn) {
+        Ok(true) => read_blobs(&conn),
+        _ => read(&conn),
+    }
+    .unwrap_or_default()
+}
+
+pub fn append_record(entry: &HistoryEntry) -> io::Result<()> {
+    let conn = open()?;
+    insert(&conn, entry).map_err(io::Error::other)?;
+    Ok(())
+}
+
+pub fn replace_last_record(entry: &HistoryEntry) -> io::Result<()> {
+    let conn = open()?;
+    let tx = conn.unchecked_transaction().map_err(io::Error::other)?;
+    let deleted = tx
+        .execute("DELETE FROM sessions WHERE id = (SELECT MAX(id) FROM sessions)", [])
+        .map_err(io::Error::other)?;
+    if deleted == 0 {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "history is empty"));
+    }
+    insert(&tx, entry).map_err(io::Error::other)?;
+    tx.commit().map_err(io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry_at(hour: u32, duration_secs: u64, skipped: bool) -> HistoryEntry {
+        HistoryEntry {
+            duration_secs,
+            label: Some("client A".to_string()),
+            interruptions: 2,
+            rating: Some(4),
+            skipped,
+            ..HistoryEntry::at(Local.with_ymd_and_hms(2024, 5, 1, hour, 0, 0).unwrap())
+        }
+    }
+
+    #[test]
+    fn records_are_stored_as_columns() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn).unwrap();
+        let records = vec![entry_at(9, 1500, false), entry_at(10, 600, true), entry_at(11, 1500, false)];
+        for record in &records {
+            insert(&conn, record).unwrap();
+        }
+        assert_eq!(read(&conn).unwrap(), records);
+
+        let (sessions, secs): (u32, u64) = conn
+            .query_row(
+                "SELECT COUNT(*), SUM(duration_secs) FROM sessions WHERE NOT skipped
+                 GROUP BY date(timestamp, 'localtime')",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((sessions, secs), (2, 3000));
+    }
+
+    #[test]
+    fn json_blobs_are_converted() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("CREATE TABLE sessions (id INTEGER PRIMARY KEY, timestamp TEXT NOT NULL, record TEXT NOT NULL)", [])
+            .unwrap();
+        let record = entry_at(9, 1500, false);
+        conn.execute(
+            "INSERT INTO sessions (timestamp, record) VALUES (?1, ?2)",
+            params![record.timestamp.to_rfc3339(), serde_json::to_string(&record).unwrap()],
+        )
+        .unwrap();
+        assert_eq!(read_blobs(&conn).unwrap(), std::slice::from_ref(&record));
+
+        migrate(&conn).unwrap();
+        assert!(!has_record_column(&conn).unwrap());
+        assert_eq!(read(&conn).unwrap(), [record]);
+    }
+}
@@ -0,0 +1,163 @@
+use crate::history::HistoryEntry;
+use chrono::{Duration, Utc};
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration as StdDuration, Instant};
+
+const CALENDAR_END: &str = "END:VCALENDAR\r\n";
+// Give up on a lock held this long; its owner most likely crashed
+const LOCK_TIMEOUT: StdDuration = StdDuration::from_secs(2);
+
+// Adds the session as a VEVENT to an .ics calendar, creating the file if
+// needed. A sidecar lockfile keeps two instances from interleaving writes
+pub fn append_event(path: &Path, entry: &HistoryEntry) -> io::Result<()> {
+    let _lock = FileLock::acquire(path)?;
+    let existing = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    let calendar = insert_event(&existing, &vevent(entry));
+
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("ics.tmp");
+    fs::write(&tmp, calendar)?;
+    fs::rename(&tmp, path)
+}
+
+// New calendars get the VCALENDAR wrapper; existing ones get the event
+// just before their closing line
+fn insert_event(calendar: &str, event: &str) -> String {
+    match calendar.rfind("END:VCALENDAR") {
+        Some(end) => format!("{}{}{}", &calendar[..end], event, CALENDAR_END),
+        None => format!(
+            "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//pomodoro-timer//EN\r\n{}{}",
+            event, CALENDAR_END
+        ),
+    }
+}
+
+fn vevent(entry: &HistoryEntry) -> String {
+    let end = entry.timestamp.with_timezone(&Utc);
+    let start = end - Duration::seconds(entry.duration_secs as i64);
+    let stamp = |time: chrono::DateTime<Utc>| time.format("%Y%m%dT%H%M%SZ").to_string();
+    let summary = entry
+        .tag
+        .as_deref()
+        .or(entry.label.as_deref())
+        .or(entry.task.as_deref())
+        .unwrap_or("Pomodoro");
+
+    let mut lines = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}-{}@pomodoro-timer", stamp(end), entry.session),
+        format!("DTSTAMP:{}", stamp(end)),
+        format!("DTSTART:{}", stamp(start)),
+        format!("DTEND:{}", stamp(end)),
+        format!("SUMMARY:{}", escape(summary)),
+    ];
+    if let Some(task) = &entry.task {
+        lines.push(format!("DESCRIPTION:{}", escape(task)));
+    }
+    lines.push("END:VEVENT".to_string());
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+// RFC 5545 text escaping
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// Lines longer than 75 octets continue on the next line after a space
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    fn acquire(target: &Path) -> io::Result<Self> {
+        let mut name = target.as_os_str().to_owned();
+        name.push(".lock");
+        let path = PathBuf::from(name);
+        let started = Instant::now();
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(Self { path }),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    if started.elapsed() >= LOCK_TIMEOUT {
+                        // Stale: take it over
+                        return Ok(Self { path });
+                    }
+                    thread::sleep(StdDuration::from_millis(20));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    fn entry() -> HistoryEntry {
+        HistoryEntry {
+            session: 2,
+            task: Some("Write report, part 1".to_string()),
+            ..HistoryEntry::at(Utc.with_ymd_and_hms(2025, 1, 14, 12, 25, 0).unwrap().with_timezone(&Local))
+        }
+    }
+
+    #[test]
+    fn event_has_start_end_and_escaped_summary() {
+        let event = vevent(&entry());
+        assert!(event.contains("DTSTART:20250114T120000Z\r\n"));
+        assert!(event.contains("DTEND:20250114T122500Z\r\n"));
+        assert!(event.contains("SUMMARY:Write report\\, part 1\r\n"));
+        assert!(event.starts_with("BEGIN:VEVENT\r\n") && event.ends_with("END:VEVENT\r\n"));
+    }
+
+    #[test]
+    fn events_go_inside_the_calendar() {
+        let first = insert_event("", "EVENT1\r\n");
+        assert!(first.starts_with("BEGIN:VCALENDAR\r\n"));
+        let second = insert_event(&first, "EVENT2\r\n");
+        assert!(second.ends_with("EVENT1\r\nEVENT2\r\nEND:VCALENDAR\r\n"));
+        assert_eq!(second.matches("BEGIN:VCALENDAR").count(), 1);
+    }
+
+    #[test]
+    fn long_lines_are_folded() {
+        let folded = fold(&format!("SUMMARY:{}", "x".repeat(100)));
+        assert!(folded.lines().all(|line| line.len() <= 75));
+        assert!(folded.contains("\r\n x"));
+    }
+}
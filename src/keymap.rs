@@ -0,0 +1,454 @@
+use crate::log;
+use crate::timer::AppState;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    TogglePause,
+    // Ends the current timer early and moves on as if it had run out
+    Skip,
+    // Ends the current timer and any break after it: straight to work
+    SkipToWork,
+    AddMinute,
+    SubMinute,
+    TaskDone,
+    Command,
+    Interruption,
+    RunProgress,
+    CycleTheme,
+    SaveTheme,
+    Defer,
+    EditTime,
+    Restart,
+    EditLabel,
+    StartBreak,
+    SkipBreak,
+    ToggleMute,
+    VolumeUp,
+    VolumeDown,
+    // Any screen, outside of text entry
+    Quit,
+    // Setup screen
+    FocusNext,
+    FocusPrev,
+    Start,
+    OpenStats,
+    ToggleAutoStart,
+    // ←/→ on setup: the focus task while its picker is focused, the
+    // preset otherwise
+    PrevChoice,
+    NextChoice,
+    // Back to setup from the stats screen
+    Back,
+    StartWork,
+    NextStep,
+    SkipRitual,
+}
+
+impl Action {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pause" => Some(Action::TogglePause),
+            "skip" => Some(Action::Skip),
+            "skip_to_work" => Some(Action::SkipToWork),
+            "add_minute" => Some(Action::AddMinute),
+            "sub_minute" => Some(Action::SubMinute),
+            "task_done" => Some(Action::TaskDone),
+            "command" => Some(Action::Command),
+            "interruption" => Some(Action::Interruption),
+            "run_progress" => Some(Action::RunProgress),
+            "cycle_theme" => Some(Action::CycleTheme),
+            "save_theme" => Some(Action::SaveTheme),
+            "defer" => Some(Action::Defer),
+            "edit_time" => Some(Action::EditTime),
+            "restart" => Some(Action::Restart),
+            "edit_label" => Some(Action::EditLabel),
+            "start_break" => Some(Action::StartBreak),
+            "skip_break" => Some(Action::SkipBreak),
+            "mute" => Some(Action::ToggleMute),
+            "volume_up" => Some(Action::VolumeUp),
+            "volume_down" => Some(Action::VolumeDown),
+            "quit" => Some(Action::Quit),
+            _ => None,
+        }
+    }
+}
+
+// The keys the app reacts to, independent of the terminal library
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Key {
+    Char(char),
+    Enter,
+    Tab,
+    Esc,
+    Backspace,
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[cfg(feature = "tui")]
+impl Key {
+    // None for keys nothing is ever bound to, like F1
+    pub fn from_code(code: crossterm::event::KeyCode) -> Option<Self> {
+        use crossterm::event::KeyCode;
+        match code {
+            KeyCode::Char(c) => Some(Key::Char(c)),
+            KeyCode::Enter => Some(Key::Enter),
+            KeyCode::Tab => Some(Key::Tab),
+            KeyCode::Esc => Some(Key::Esc),
+            KeyCode::Backspace => Some(Key::Backspace),
+            KeyCode::Up => Some(Key::Up),
+            KeyCode::Down => Some(Key::Down),
+            KeyCode::Left => Some(Key::Left),
+            KeyCode::Right => Some(Key::Right),
+            _ => None,
+        }
+    }
+}
+
+// A `[keys]` entry is either one key or a list of keys
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum KeyBinding {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyBinding {
+    fn keys(&self) -> Vec<&str> {
+        match self {
+            KeyBinding::One(key) => vec![key.as_str()],
+            KeyBinding::Many(keys) => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+// ENTER starts the timer on setup and the prompts, so a double tap or key
+// repeat lands here too; pausing is the one action that's undone as easily
+const RUNNING_DEFAULTS: &[(Action, &[Key])] = &[
+    (Action::TogglePause, &[Key::Char(' '), Key::Enter]),
+    (Action::Skip, &[Key::Char('s')]),
+    (Action::SkipToWork, &[Key::Char('n')]),
+    (Action::AddMinute, &[Key::Up]),
+    (Action::SubMinute, &[Key::Down]),
+    (Action::TaskDone, &[Key::Char('d')]),
+    (Action::Command, &[Key::Char(':')]),
+    (Action::Interruption, &[Key::Char('i')]),
+    (Action::RunProgress, &[Key::Char('p')]),
+    (Action::CycleTheme, &[Key::Char('T')]),
+    (Action::SaveTheme, &[Key::Char('W')]),
+    (Action::Defer, &[Key::Char('m')]),
+    (Action::EditTime, &[Key::Char('e')]),
+    (Action::Restart, &[Key::Char('r')]),
+    (Action::EditLabel, &[Key::Char('l')]),
+    (Action::ToggleMute, &[Key::Char('M')]),
+    (Action::VolumeUp, &[Key::Char('+'), Key::Char(']')]),
+    (Action::VolumeDown, &[Key::Char('-'), Key::Char('[')]),
+    (Action::Quit, &[Key::Char('q')]),
+];
+
+// SPACE starts the break too, so it works the same as ENTER
+const PROMPT_DEFAULTS: &[(Action, &[Key])] = &[
+    (Action::StartBreak, &[Key::Enter, Key::Char(' ')]),
+    (Action::SkipBreak, &[Key::Char('s')]),
+    (Action::ToggleMute, &[Key::Char('M')]),
+    (Action::Quit, &[Key::Char('q')]),
+];
+
+// Actions listed in the generated help lines, in display order
+const RUNNING_HELP: &[(Action, &str)] = &[
+    (Action::TogglePause, "Pause"),
+    (Action::Skip, "Skip"),
+    (Action::SkipToWork, "To Work"),
+    (Action::AddMinute, "+1m"),
+    (Action::SubMinute, "-1m"),
+    (Action::EditTime, "Set Time"),
+    (Action::Restart, "Restart"),
+    (Action::EditLabel, "Label"),
+    (Action::Interruption, "Interruption"),
+    (Action::TaskDone, "Task Done"),
+    (Action::Defer, "Meeting"),
+    (Action::Command, "Command"),
+    (Action::ToggleMute, "Mute"),
+    (Action::Quit, "Quit"),
+];
+
+const PROMPT_HELP: &[(Action, &str)] = &[
+    (Action::StartBreak, "Start Break"),
+    (Action::SkipBreak, "Skip"),
+    (Action::ToggleMute, "Mute"),
+    (Action::Quit, "Quit"),
+];
+
+const OVERTIME_HELP: &[(Action, &str)] = &[
+    (Action::Skip, "Take Break"),
+    (Action::SkipToWork, "To Work"),
+    (Action::Quit, "Quit"),
+];
+
+// Each action with its keys, in the order they were bound
+type Bindings = Vec<(Action, Vec<Key>)>;
+
+pub struct Keymap {
+    running: Bindings,
+    prompt: Bindings,
+}
+
+impl Keymap {
+    pub fn new(overrides: &HashMap<String, KeyBinding>) -> Self {
+        report_invalid(overrides);
+        Self {
+            running: build(RUNNING_DEFAULTS, overrides),
+            prompt: build(PROMPT_DEFAULTS, overrides),
+        }
+    }
+
+    pub fn running_action(&self, key: Key) -> Option<Action> {
+        lookup(&self.running, key)
+    }
+
+    pub fn prompt_action(&self, key: Key) -> Option<Action> {
+        lookup(&self.prompt, key)
+    }
+
+    // What a key means in the given state. Only the running timer, the
+    // break prompt and quitting follow `[keys]`; the other screens have fixed
+    // keys. Typing into a setup field is text entry rather than an action, so
+    // digits and BACKSPACE there give None
+    pub fn map_key(&self, state: AppState, key: Key) -> Option<Action> {
+        match state {
+            // Only skipping, muting and quitting do anything in overtime
+            AppState::Running | AppState::Overtime => self.running_action(key),
+            AppState::BreakPrompt | AppState::BreakLeadIn => self.prompt_action(key),
+            _ if self.running_action(key) == Some(Action::Quit) => Some(Action::Quit),
+            AppState::Setup => match key {
+                Key::Tab | Key::Down => Some(Action::FocusNext),
+                Key::Up => Some(Action::FocusPrev),
+                Key::Enter => Some(Action::Start),
+                Key::Char('t') => Some(Action::OpenStats),
+                Key::Char('a') => Some(Action::ToggleAutoStart),
+                Key::Left => Some(Action::PrevChoice),
+                Key::Right => Some(Action::NextChoice),
+                _ => None,
+            },
+            AppState::WorkPrompt => (key == Key::Enter).then_some(Action::StartWork),
+            AppState::Stats => (key == Key::Esc).then_some(Action::Back),
+            AppState::Ritual => match key {
+                Key::Enter => Some(Action::NextStep),
+                Key::Char('s') | Key::Esc => Some(Action::SkipRitual),
+                _ => None,
+            },
+        }
+    }
+
+    // "[SPACE] Pause  •  [s] Skip  •  ..." for the current bindings
+    pub fn running_help(&self, has_task: bool) -> String {
+        help_line(&self.running, RUNNING_HELP, |action| {
+            has_task || action != Action::TaskDone
+        })
+    }
+
+    pub fn prompt_help(&self) -> String {
+        help_line(&self.prompt, PROMPT_HELP, |_| true)
+    }
+
+    pub fn overtime_help(&self) -> String {
+        help_line(&self.running, OVERTIME_HELP, |_| true)
+    }
+
+    // "[q] Quit" for the screens whose other keys are fixed
+    pub fn quit_help(&self) -> String {
+        help_line(&self.running, &[(Action::Quit, "Quit")], |_| true)
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&HashMap::new())
+    }
+}
+
+// A configured action replaces all of its default keys, and takes those
+// keys away from whichever action had them by default. Overrides apply in
+// name order, so two claiming the same key always resolve the same way
+fn build(defaults: &[(Action, &[Key])], overrides: &HashMap<String, KeyBinding>) -> Bindings {
+    let mut bindings: Bindings = defaults
+        .iter()
+        .map(|(action, keys)| (*action, keys.to_vec()))
+        .collect();
+
+    let mut overrides: Vec<_> = overrides.iter().collect();
+    overrides.sort_by_key(|(name, _)| name.as_str());
+    for (name, binding) in overrides {
+        let Some(action) = Action::from_name(name) else {
+            continue;
+        };
+        let keys: Vec<Key> = binding.keys().into_iter().filter_map(parse_key).collect();
+        bind(&mut bindings, action, keys);
+    }
+
+    // Quitting always keeps a key: an empty `quit`, or another action
+    // taking its keys, gives it back its default
+    let quit_unbound = bindings.iter().any(|(action, keys)| *action == Action::Quit && keys.is_empty());
+    if let Some((_, keys)) = defaults.iter().find(|(action, _)| *action == Action::Quit).filter(|_| quit_unbound) {
+        log::debug("⚠ [keys] quit: left without a key, default restored");
+        bind(&mut bindings, Action::Quit, keys.to_vec());
+    }
+    bindings
+}
+
+// Gives `keys` to `action` alone
+fn bind(bindings: &mut Bindings, action: Action, keys: Vec<Key>) {
+    let Some(index) = bindings.iter().position(|(a, _)| *a == action) else {
+        return;
+    };
+    for (_, bound) in bindings.iter_mut() {
+        bound.retain(|key| !keys.contains(key));
+    }
+    bindings[index].1 = keys;
+}
+
+// Unknown actions and key names are left out of the keymap rather than
+// failing the config; --debug says which
+fn report_invalid(overrides: &HashMap<String, KeyBinding>) {
+    for (name, binding) in overrides {
+        if Action::from_name(name).is_none() {
+            log::debug(&format!("⚠ [keys] {}: unknown action, ignored", name));
+            continue;
+        }
+        for key in binding.keys() {
+            if parse_key(key).is_none() {
+                log::debug(&format!("⚠ [keys] {}: unknown key {:?}, ignored", name, key));
+            }
+        }
+    }
+}
+
+fn lookup(bindings: &Bindings, key: Key) -> Option<Action> {
+    bindings
+        .iter()
+        .find(|(_, keys)| keys.contains(&key))
+        .map(|(action, _)| *action)
+}
+
+// Unbound actions are left out
+fn help_line(bindings: &Bindings, labels: &[(Action, &str)], show: impl Fn(Action) -> bool) -> String {
+    let parts: Vec<String> = labels
+        .iter()
+        .filter(|(action, _)| show(*action))
+        .filter_map(|(action, label)| {
+            let (_, keys) = bindings.iter().find(|(a, _)| a == action)?;
+            if keys.is_empty() {
+                return None;
+            }
+            let keys: Vec<String> = keys.iter().map(|key| key_label(*key)).collect();
+            Some(format!("[{}] {}", keys.join("/"), label))
+        })
+        .collect();
+    parts.join("  •  ")
+}
+
+fn key_label(key: Key) -> String {
+    match key {
+        Key::Char(' ') => "SPACE".to_string(),
+        Key::Char(c) => c.to_string(),
+        Key::Enter => "ENTER".to_string(),
+        Key::Tab => "TAB".to_string(),
+        Key::Esc => "ESC".to_string(),
+        Key::Backspace => "BACKSPACE".to_string(),
+        Key::Up => "↑".to_string(),
+        Key::Down => "↓".to_string(),
+        Key::Left => "←".to_string(),
+        Key::Right => "→".to_string(),
+    }
+}
+
+pub fn parse_key(name: &str) -> Option<Key> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(Key::Char(c));
+    }
+    match name.to_ascii_lowercase().as_str() {
+        "space" => Some(Key::Char(' ')),
+        "enter" => Some(Key::Enter),
+        "tab" => Some(Key::Tab),
+        "esc" => Some(Key::Esc),
+        "backspace" => Some(Key::Backspace),
+        "up" => Some(Key::Up),
+        "down" => Some(Key::Down),
+        "left" => Some(Key::Left),
+        "right" => Some(Key::Right),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn help_follows_rebound_keys() {
+        let mut overrides = HashMap::new();
+        overrides.insert("pause".to_string(), KeyBinding::Many(vec!["space".into(), "enter".into()]));
+        let keymap = Keymap::new(&overrides);
+
+        assert_eq!(keymap.running_action(Key::Enter), Some(Action::TogglePause));
+        let help = keymap.running_help(false);
+        assert!(help.starts_with("[SPACE/ENTER] Pause  •  [s] Skip  •  [n] To Work  •  "));
+        assert!(!help.contains("Task Done"));
+        assert!(help.ends_with("[q] Quit"));
+    }
+
+    #[test]
+    fn quit_can_be_rebound_and_bad_keys_are_ignored() {
+        let mut overrides = HashMap::new();
+        overrides.insert("quit".to_string(), KeyBinding::One("x".into()));
+        overrides.insert("skip".to_string(), KeyBinding::Many(vec!["n".into(), "F13".into()]));
+        overrides.insert("teleport".to_string(), KeyBinding::One("t".into()));
+        let keymap = Keymap::new(&overrides);
+
+        assert_eq!(keymap.map_key(AppState::Stats, Key::Char('x')), Some(Action::Quit));
+        assert_eq!(keymap.map_key(AppState::Stats, Key::Char('q')), None);
+        assert_eq!(keymap.map_key(AppState::Running, Key::Char('n')), Some(Action::Skip));
+        assert_eq!(keymap.quit_help(), "[x] Quit");
+        assert!(keymap.prompt_help().ends_with("[x] Quit"));
+
+        overrides.insert("quit".to_string(), KeyBinding::One("nonsense".into()));
+        let keymap = Keymap::new(&overrides);
+        assert_eq!(keymap.map_key(AppState::Setup, Key::Char('q')), Some(Action::Quit));
+    }
+
+    #[test]
+    fn quit_gets_its_key_back_and_overrides_resolve_in_name_order() {
+        let mut overrides = HashMap::new();
+        overrides.insert("skip".to_string(), KeyBinding::One("q".into()));
+        let keymap = Keymap::new(&overrides);
+        assert_eq!(keymap.map_key(AppState::Running, Key::Char('q')), Some(Action::Quit));
+        assert_eq!(keymap.map_key(AppState::Setup, Key::Char('q')), Some(Action::Quit));
+
+        // "restart" comes after "pause", so it ends up with the key
+        for _ in 0..10 {
+            let mut overrides = HashMap::new();
+            overrides.insert("pause".to_string(), KeyBinding::One("x".into()));
+            overrides.insert("restart".to_string(), KeyBinding::One("x".into()));
+            let keymap = Keymap::new(&overrides);
+            assert_eq!(keymap.running_action(Key::Char('x')), Some(Action::Restart));
+        }
+    }
+
+    #[test]
+    fn same_key_maps_by_state() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.map_key(AppState::Running, Key::Enter), Some(Action::TogglePause));
+        assert_eq!(keymap.map_key(AppState::Running, Key::Char('s')), Some(Action::Skip));
+        assert_eq!(keymap.map_key(AppState::BreakPrompt, Key::Enter), Some(Action::StartBreak));
+        assert_eq!(keymap.map_key(AppState::Setup, Key::Enter), Some(Action::Start));
+        assert_eq!(keymap.map_key(AppState::WorkPrompt, Key::Enter), Some(Action::StartWork));
+        assert_eq!(keymap.map_key(AppState::Stats, Key::Enter), None);
+        assert_eq!(keymap.map_key(AppState::Setup, Key::Char('5')), None);
+        assert_eq!(keymap.map_key(AppState::Ritual, Key::Char('q')), Some(Action::Quit));
+    }
+}
@@ -0,0 +1,2029 @@
+// The timer without its terminal UI, for embedding: build a PomodoroApp
+// from a Config, drive it with `input` and `sync_clock` (or `tick`) and
+// carry out the returned effects
+use alert::{Alert, AlertSink, SpeakerAlerts};
+use audio::Audio;
+use chrono::{DateTime, Local, NaiveDate};
+use clock::WallClock;
+use config::{Config, Preset};
+use history::HistoryEntry;
+use keymap::{Key, Keymap};
+use notifier::{DesktopNotifier, Notifier};
+use playlist::Playlist;
+use serde::Serialize;
+use state::{LiveStatus, SessionState};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use theme::{Theme, THEMES};
+use todo::TodoItem;
+use tone::CountdownPattern;
+
+pub mod alert;
+pub mod audio;
+pub mod clock;
+pub mod config;
+pub mod error;
+pub mod events;
+pub mod export;
+pub mod history;
+pub mod ical;
+pub mod keymap;
+pub mod log;
+pub mod notifier;
+pub mod playlist;
+pub mod selftest;
+pub mod state;
+pub mod stats;
+pub mod theme;
+pub mod timer;
+pub mod tone;
+pub mod todo;
+
+pub use keymap::Action;
+pub use timer::{AppState, Effect, TimerType};
+
+// Setup field labels and units, in input order
+pub const FIELD_LABELS: [(&str, &str); 6] = [
+    ("Work Duration", "minutes, "),
+    ("Short Break", "minutes, "),
+    ("Long Break", "minutes, "),
+    ("Total Sessions", ""),
+    ("Long Break Every", "sessions, 0 = never, "),
+    ("Volume", "%, "),
+];
+
+// `+1m` stops here, keeping the big digits within their width
+pub const MAX_TIME_LEFT: Duration = Duration::from_secs(180 * 60);
+
+// Volume keys move in steps of this much
+pub const VOLUME_STEP: f32 = 0.1;
+
+// How much of the countdown a `tick_sound` covers when `countdown_ticks`
+// is left at 0
+pub const TICK_SOUND_SECS: u64 = 10;
+
+// Fits the setup input box
+pub const LABEL_MAX_CHARS: usize = 32;
+
+// Narrower margins than this get no side panels
+pub const MIN_PANEL_WIDTH: u16 = 16;
+
+// Recorded when the skip reason picker is dismissed
+pub const NO_SKIP_REASON: &str = "no reason";
+
+pub struct InputField {
+    pub value: String,
+    pub placeholder: String,
+    pub focused: bool,
+    pub min: u32,
+    pub max: u32,
+}
+
+impl InputField {
+    pub fn new(placeholder: &str, (min, max): (u32, u32)) -> Self {
+        Self {
+            value: String::new(),
+            placeholder: placeholder.to_string(),
+            focused: false,
+            min,
+            max: max.max(min),
+        }
+    }
+
+    // Blank means `default`; anything else has to pass `validate` rather
+    // than silently becoming the default
+    pub fn get_value(&self, default: u32) -> Result<u32, String> {
+        if self.value.trim().is_empty() {
+            return Ok(default.clamp(self.min, self.max));
+        }
+        self.validate()
+    }
+    
+    // The whole number in the box (the placeholder while blank), if it's
+    // within `min`..=`max`
+    pub fn validate(&self) -> Result<u32, String> {
+        self.shown()
+            .parse::<u32>()
+            .ok()
+            .filter(|n| (self.min..=self.max).contains(n))
+            .ok_or_else(|| self.range_hint())
+    }
+    
+    // Shown under a box holding something `validate` rejects
+    pub fn range_hint(&self) -> String {
+        format!("must be {}–{}", self.min, self.max)
+    }
+    
+    fn shown(&self) -> &str {
+        match self.value.trim() {
+            "" => self.placeholder.trim(),
+            value => value,
+        }
+    }
+    
+    // Rejects keystrokes that would take the value past `max`
+    pub fn push_digit(&mut self, c: char) {
+        let candidate = format!("{}{}", self.value, c);
+        let fits = candidate.len() <= self.max.to_string().len()
+            && candidate.parse::<u32>().is_ok_and(|n| n <= self.max);
+        if fits {
+            self.value = candidate;
+        }
+    }
+    
+    // Time fields take "MM:SS"; digits plus a single colon, at most two
+    // digits after it
+    pub fn push_time_char(&mut self, c: char) {
+        let fits = match self.value.split_once(':') {
+            Some((_, secs)) => c.is_ascii_digit() && secs.len() < 2,
+            None => c == ':' || (c.is_ascii_digit() && self.value.len() < self.max.to_string().len()),
+        };
+        if fits {
+            self.value.push(c);
+        }
+    }
+    
+    // "MM:SS" or plain minutes, with `min`..=`max` minutes in total
+    pub fn get_time(&self) -> Option<Duration> {
+        let total = parse_time(self.value.trim())?;
+        let in_range = total > 0 && total >= self.min as u64 * 60 && total <= self.max as u64 * 60;
+        in_range.then(|| Duration::from_secs(total))
+    }
+    
+    // Setup duration fields: like `get_value`, but in "MM:SS" or plain
+    // minutes, between `min` and `max` minutes (and at least a second)
+    pub fn get_duration(&self, default: Duration) -> Result<Duration, String> {
+        if self.value.trim().is_empty() {
+            let secs = default.as_secs().clamp((self.min as u64 * 60).max(1), self.max as u64 * 60);
+            return Ok(Duration::from_secs(secs));
+        }
+        let in_range = |secs: &u64| *secs > 0 && (self.min as u64 * 60..=self.max as u64 * 60).contains(secs);
+        parse_time(self.shown())
+            .filter(in_range)
+            .map(Duration::from_secs)
+            .ok_or_else(|| self.range_hint())
+    }
+}
+
+// Seconds in "MM:SS" or plain minutes; "5:" counts as five minutes
+pub fn parse_time(text: &str) -> Option<u64> {
+    let (minutes, secs) = text.split_once(':').unwrap_or((text, "0"));
+    let minutes: u64 = minutes.parse().ok()?;
+    let secs: u64 = if secs.is_empty() { 0 } else { secs.parse().ok()? };
+    (secs < 60).then_some(minutes * 60 + secs)
+}
+
+// What an embedder needs to draw its own display; also what `--serve`
+// answers GET /state with
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct Status {
+    pub state: AppState,
+    pub timer_type: TimerType,
+    pub secs_left: u64,
+    pub session: u32,
+    pub total_sessions: u32,
+    pub paused: bool,
+    pub label: Option<String>,
+}
+
+// What the app shows from disk: the history for the goal count and the
+// streak, the todo file's open tasks and the action planned last time.
+// `PomodoroApp::new` reads none of it; the binary loads it up front
+#[derive(Clone, Debug, Default)]
+pub struct AppData {
+    pub records: Vec<HistoryEntry>,
+    pub todos: Vec<TodoItem>,
+    pub planned_action: Option<String>,
+}
+
+impl AppData {
+    pub fn load(config: &Config) -> Self {
+        Self {
+            records: history::load_records(),
+            todos: config.todo_file.as_deref().map(|path| todo::load_todos(Path::new(path))).unwrap_or_default(),
+            planned_action: state::load_next_action(),
+        }
+    }
+}
+
+pub struct PomodoroApp {
+    pub state: AppState,
+    pub timer_type: TimerType,
+    pub paused: bool,
+    
+    // Input fields
+    pub inputs: Vec<InputField>,
+    // Free-text task label, focused right after the number fields
+    pub label_input: InputField,
+    pub focus_index: usize,
+    pub setup_error: Option<String>,
+    
+    // Read from history when the stats screen opens
+    pub stats: stats::Summary,
+    
+    // Focus ritual prompts and the one being shown
+    pub ritual: Vec<String>,
+    pub ritual_index: usize,
+    
+    // Go from a finished work session straight into the break
+    pub auto_start_breaks: bool,
+    // ...and from a finished break straight into work
+    pub auto_start_work: bool,
+    pub overtime: bool,
+    // --once: a single work session that ends the run without a break
+    pub once: bool,
+    // When the work countdown hit zero, while in overtime
+    pub overtime_since: Option<Instant>,
+    // Overtime of the session just ended, for its history entry
+    pub overtime_done: Duration,
+    
+    // Reasons offered when skipping a break, and the highlighted one while
+    // the picker is open
+    pub ask_skip_reason: bool,
+    pub skip_reasons: Vec<String>,
+    pub skip_reason_choice: Option<usize>,
+    
+    pub keymap: Keymap,
+    
+    pub theme: Theme,
+    // `[colors]` overrides, kept on top of whichever theme is picked
+    pub theme_colors: HashMap<String, String>,
+    
+    // Floors for the -1m key
+    pub min_remaining: config::MinRemaining,
+    
+    // Timer durations
+    pub work_duration: Duration,
+    pub short_break_duration: Duration,
+    pub long_break_duration: Duration,
+    // Whether skipping a work session still counts it as done
+    pub count_skipped_work: bool,
+    // Long breaks are earned by completed work, not reached by session number
+    pub long_break_needs_completed: bool,
+    // Work sessions that ran out since the last long break
+    pub work_since_long_break: u32,
+    // Growing long breaks, one value used per long break taken
+    pub long_break_cycle: Vec<u32>,
+    pub long_breaks_taken: usize,
+    pub total_sessions: u32,
+    pub long_break_interval: u32,
+    pub current_session: u32,
+    pub time_left: Duration,
+    pub session_total: Duration,
+    // When the countdown reaches zero; None while it is stopped (paused,
+    // deferred, held or between timers) and set again when it restarts
+    pub session_end: Option<Instant>,
+    
+    // Time actually spent counting down this run, for the run percentage
+    pub run_elapsed: Duration,
+    // Work sessions run down to zero this run, and their lengths, for the
+    // summary at the end; skipped ones are left out
+    pub completed_work_sessions: u32,
+    pub focus_time: Duration,
+    pub show_run_progress: bool,
+    pub session_dots: bool,
+    pub blink_colon: bool,
+    pub font: String,
+    
+    // Per-session focus tracking, reset whenever a timer starts
+    pub pause_count: u32,
+    pub paused_total: Duration,
+    pub paused_at: Option<Instant>,
+    pub interruptions: u32,
+    
+    // Timestamps for history, guarded against clock jumps
+    pub clock: WallClock,
+    // Calendar file that gets a VEVENT per completed work session
+    pub ical_export_path: Option<String>,
+    
+    // Resume a forgotten pause after this long (zero disables)
+    pub auto_resume_after: Duration,
+    // Pause work after this long without a key (zero disables); the last
+    // key press, and whether the current pause is that kind
+    pub auto_pause_idle: Duration,
+    pub last_input: Instant,
+    pub idle_paused: bool,
+    
+    // Write the session state this often (zero disables)
+    pub autosave_every: Duration,
+    
+    // Meeting mode: no ticking and no alerts until this passes
+    pub deferred_until: Option<Instant>,
+    pub defer_duration: Duration,
+    
+    // Break prompt
+    pub next_break_type: Option<TimerType>,
+    pub break_leadin: Duration,
+    pub leadin_until: Option<Instant>,
+    
+    // `:` command line, open while Some
+    pub command_line: Option<String>,
+    // Exact remaining time being typed in, open while Some
+    pub time_edit: Option<InputField>,
+    pub command_message: Option<String>,
+    
+    // Focus task picked from the todo file
+    pub todo_path: Option<PathBuf>,
+    pub todos: Vec<TodoItem>,
+    pub todo_index: usize,
+    
+    // Setup presets, with a built-in Classic if none are configured, and
+    // the one last picked. None until a preset is picked or when the
+    // config's defaults match none
+    pub presets: Vec<Preset>,
+    pub preset_index: Option<usize>,
+    pub current_task: Option<TodoItem>,
+    // Shown under the title and saved with each session; empty for none
+    pub task_label: String,
+    // New label being typed while the timer runs, open while Some
+    pub label_edit: Option<String>,
+    
+    // Strict focus: hide the work countdown until a key reveals it
+    pub hide_clock: bool,
+    pub reveal_duration: Duration,
+    pub reveal_until: Option<Instant>,
+    
+    // Break prompt art, None falls back to the emoji title
+    pub celebration_art: Option<Vec<String>>,
+    
+    // Next action typed at the end of the last run, shown on setup
+    pub planned_action: Option<String>,
+    pub ask_next_action: bool,
+    
+    // A quit key during a session opens "Quit? [y/N]" instead of quitting
+    pub confirm_quit: bool,
+    pub quit_pending: bool,
+    
+    // Pomodoros completed on `goal_day` (local date), from the history at
+    // startup and counted on from there, and whether reaching daily_goal
+    // has been announced for that day
+    pub daily_goal: u32,
+    pub goal_day: NaiveDate,
+    pub done_today: usize,
+    pub goal_announced: bool,
+    
+    // Per-screen replacements for the generated help lines
+    pub help_overrides: HashMap<String, String>,
+    pub session_label_format: String,
+    
+    // Sound
+    pub audio: Audio,
+    pub alerts: Box<dyn AlertSink>,
+    pub notifier: Box<dyn Notifier>,
+    // Tick through the last this many seconds (zero disables)
+    pub countdown_ticks: u64,
+    pub countdown_pattern: CountdownPattern,
+    pub playlist: Playlist,
+    pub ui_sound: Option<String>,
+    pub tick_sound: Option<PathBuf>,
+    // --no-tick: no countdown ticks this run, whatever the config says
+    pub no_tick: bool,
+    pub muted: bool,
+    pub volume: f32,
+    pub vibrate: bool,
+    pub vibrate_command: Option<String>,
+    
+    // Screen size
+    pub width: u16,
+    pub height: u16,
+    pub max_content_width: u16,
+    pub side_panels: bool,
+    pub smooth_progress: bool,
+}
+
+impl PomodoroApp {
+    // With no history, todos or planned action, as in tests
+    pub fn new(config: &Config) -> Self {
+        Self::with_data(config, AppData::default())
+    }
+    
+    pub fn with_data(config: &Config, data: AppData) -> Self {
+        let limits = &config.limits;
+        let mut inputs = vec![
+            InputField::new(&config.work_minutes.to_string(), limits.work),
+            InputField::new(&config.short_break_minutes.to_string(), limits.short_break),
+            InputField::new(&config.long_break_minutes.to_string(), limits.long_break),
+            InputField::new(&config.total_sessions.to_string(), limits.sessions),
+            InputField::new(&config.long_break_interval.to_string(), limits.long_break_interval),
+            InputField::new(&volume_percent(config.volume).to_string(), (0, 100)),
+        ];
+        inputs[0].focused = true;
+        
+        let presets = if config.presets.is_empty() {
+            vec![Preset {
+                name: "Classic".to_string(),
+                work_minutes: 25,
+                short_break_minutes: 5,
+                long_break_minutes: 15,
+                total_sessions: 4,
+            }]
+        } else {
+            config.presets.clone()
+        };
+        let defaults = [config.work_minutes, config.short_break_minutes, config.long_break_minutes, config.total_sessions];
+        
+        // For the goal count and the streak shown on setup
+        let today = Local::now().date_naive();
+        let summary = stats::summarize(&data.records, today);
+        let done_today = if config.daily_goal > 0 { summary.today } else { 0 };
+        
+        Self {
+            state: AppState::Setup,
+            timer_type: TimerType::Work,
+            paused: false,
+            inputs,
+            label_input: InputField::new("optional", (0, 0)),
+            focus_index: 0,
+            keymap: Keymap::new(&config.keys),
+            theme: theme::load(&config.theme, &config.colors),
+            theme_colors: config.colors.clone(),
+            min_remaining: config.min_remaining,
+            work_duration: Duration::from_secs(config.work_minutes as u64 * 60),
+            short_break_duration: Duration::from_secs(config.short_break_minutes as u64 * 60),
+            long_break_duration: Duration::from_secs(config.long_break_minutes as u64 * 60),
+            long_break_cycle: config.long_break_cycle.iter().copied().filter(|m| *m > 0).collect(),
+            long_breaks_taken: 0,
+            count_skipped_work: config.count_skipped_work,
+            long_break_needs_completed: config.long_break_needs_completed,
+            work_since_long_break: 0,
+            total_sessions: config.total_sessions,
+            long_break_interval: config.long_break_interval,
+            current_session: 1,
+            time_left: Duration::from_secs(config.work_minutes as u64 * 60),
+            session_end: None,
+            session_total: Duration::from_secs(config.work_minutes as u64 * 60),
+            run_elapsed: Duration::ZERO,
+            completed_work_sessions: 0,
+            focus_time: Duration::ZERO,
+            show_run_progress: config.show_run_progress,
+            session_dots: config.session_dots,
+            blink_colon: config.blink_colon,
+            font: config.font.clone(),
+            pause_count: 0,
+            paused_total: Duration::ZERO,
+            paused_at: None,
+            interruptions: 0,
+            clock: WallClock::default(),
+            auto_resume_after: Duration::from_secs(config.auto_resume_after),
+            auto_pause_idle: Duration::from_secs(config.auto_pause_idle_secs),
+            last_input: Instant::now(),
+            idle_paused: false,
+            autosave_every: Duration::from_secs(config.autosave_seconds),
+            ical_export_path: config.ical_export_path.clone(),
+            break_leadin: Duration::from_secs(config.break_leadin_seconds),
+            leadin_until: None,
+            deferred_until: None,
+            defer_duration: Duration::from_secs(config.defer_minutes as u64 * 60),
+            next_break_type: None,
+            command_line: None,
+            time_edit: None,
+            setup_error: None,
+            stats: summary,
+            ritual: config.ritual.clone(),
+            ritual_index: 0,
+            auto_start_breaks: config.auto_start_breaks,
+            auto_start_work: config.auto_start_work,
+            overtime: config.overtime,
+            overtime_since: None,
+            overtime_done: Duration::ZERO,
+            ask_skip_reason: config.ask_skip_reason,
+            skip_reasons: config.skip_reasons.clone(),
+            skip_reason_choice: None,
+            command_message: None,
+            todo_path: config.todo_file.as_ref().map(PathBuf::from),
+            todos: data.todos,
+            todo_index: 0,
+            preset_index: presets.iter().position(|preset| preset.values() == defaults),
+            presets,
+            current_task: None,
+            task_label: String::new(),
+            label_edit: None,
+            hide_clock: config.hide_clock,
+            reveal_duration: Duration::from_secs(config.reveal_seconds),
+            reveal_until: None,
+            celebration_art: config.celebration_art.as_deref().and_then(load_art),
+            planned_action: data.planned_action,
+            ask_next_action: config.ask_next_action,
+            confirm_quit: config.confirm_quit,
+            quit_pending: false,
+            daily_goal: config.daily_goal,
+            goal_day: today,
+            done_today,
+            goal_announced: config.daily_goal > 0 && done_today >= config.daily_goal as usize,
+            help_overrides: config.help_overrides.clone(),
+            session_label_format: config.session_label_format.clone(),
+            audio: Audio::new(),
+            alerts: Box::new(SpeakerAlerts),
+            notifier: Box::new(DesktopNotifier),
+            countdown_ticks: config.countdown_ticks,
+            countdown_pattern: config.countdown_pattern,
+            playlist: Playlist::load(
+                config.sound_path.as_deref().map(Path::new),
+                config.sound_seed,
+            ),
+            ui_sound: config.ui_sound.clone(),
+            tick_sound: config.tick_sound.as_ref().map(PathBuf::from),
+            no_tick: false,
+            once: false,
+            muted: config.muted,
+            volume: config.volume.clamp(0.0, 1.0),
+            vibrate: config.vibrate,
+            vibrate_command: config.vibrate_command.clone(),
+            width: 0,
+            height: 0,
+            max_content_width: config.max_content_width,
+            side_panels: config.side_panels,
+            smooth_progress: config.smooth_progress,
+        }
+    }
+    
+    // Applies settings edited while the timer runs. Returns the keys that
+    // took effect and those that only apply on the next start
+    pub fn reload(&mut self, old: &Config, new: &Config) -> (Vec<&'static str>, Vec<&'static str>) {
+        let mut applied = Vec::new();
+        let mut deferred = Vec::new();
+        
+        if old.sound_path != new.sound_path || old.sound_seed != new.sound_seed {
+            self.playlist = Playlist::load(
+                new.sound_path.as_deref().map(Path::new),
+                new.sound_seed,
+            );
+            applied.push("sound");
+        }
+        if old.ui_sound != new.ui_sound {
+            self.ui_sound = new.ui_sound.clone();
+            applied.push("ui_sound");
+        }
+        if old.tick_sound != new.tick_sound {
+            self.tick_sound = new.tick_sound.as_ref().map(PathBuf::from);
+            applied.push("tick_sound");
+        }
+        if old.muted != new.muted {
+            self.muted = new.muted;
+            applied.push("muted");
+        }
+        if old.volume != new.volume {
+            self.volume = new.volume.clamp(0.0, 1.0);
+            applied.push("volume");
+        }
+        if old.vibrate != new.vibrate || old.vibrate_command != new.vibrate_command {
+            self.vibrate = new.vibrate;
+            self.vibrate_command = new.vibrate_command.clone();
+            applied.push("vibrate");
+        }
+        if old.theme != new.theme || old.colors != new.colors {
+            self.theme = theme::load(&new.theme, &new.colors);
+            self.theme_colors = new.colors.clone();
+            applied.push("theme");
+        }
+        if old.keys != new.keys {
+            self.keymap = Keymap::new(&new.keys);
+            applied.push("keys");
+        }
+        if old.help_overrides != new.help_overrides {
+            self.help_overrides = new.help_overrides.clone();
+            applied.push("help_overrides");
+        }
+        if old.session_label_format != new.session_label_format {
+            self.session_label_format = new.session_label_format.clone();
+            applied.push("session_label_format");
+        }
+        if old.celebration_art != new.celebration_art {
+            self.celebration_art = new.celebration_art.as_deref().and_then(load_art);
+            applied.push("celebration_art");
+        }
+        if old.hide_clock != new.hide_clock || old.reveal_seconds != new.reveal_seconds {
+            self.hide_clock = new.hide_clock;
+            self.reveal_duration = Duration::from_secs(new.reveal_seconds);
+            applied.push("hide_clock");
+        }
+        if old.min_remaining != new.min_remaining {
+            self.min_remaining = new.min_remaining;
+            applied.push("min_remaining");
+        }
+        if old.auto_resume_after != new.auto_resume_after {
+            self.auto_resume_after = Duration::from_secs(new.auto_resume_after);
+            applied.push("auto_resume_after");
+        }
+        if old.auto_pause_idle_secs != new.auto_pause_idle_secs {
+            self.auto_pause_idle = Duration::from_secs(new.auto_pause_idle_secs);
+            applied.push("auto_pause_idle_secs");
+        }
+        if old.show_run_progress != new.show_run_progress {
+            self.show_run_progress = new.show_run_progress;
+            applied.push("show_run_progress");
+        }
+        if old.session_dots != new.session_dots {
+            self.session_dots = new.session_dots;
+            applied.push("session_dots");
+        }
+        if old.blink_colon != new.blink_colon {
+            self.blink_colon = new.blink_colon;
+            applied.push("blink_colon");
+        }
+        if old.font != new.font {
+            self.font = new.font.clone();
+            applied.push("font");
+        }
+        if old.confirm_quit != new.confirm_quit {
+            self.confirm_quit = new.confirm_quit;
+            applied.push("confirm_quit");
+        }
+        if old.countdown_ticks != new.countdown_ticks || old.countdown_pattern != new.countdown_pattern {
+            self.countdown_ticks = new.countdown_ticks;
+            self.countdown_pattern = new.countdown_pattern;
+            applied.push("countdown");
+        }
+        if old.defer_minutes != new.defer_minutes {
+            self.defer_duration = Duration::from_secs(new.defer_minutes as u64 * 60);
+            applied.push("defer_minutes");
+        }
+        if old.autosave_seconds != new.autosave_seconds {
+            self.autosave_every = Duration::from_secs(new.autosave_seconds);
+            applied.push("autosave_seconds");
+        }
+        if old.break_leadin_seconds != new.break_leadin_seconds {
+            self.break_leadin = Duration::from_secs(new.break_leadin_seconds);
+            applied.push("break_leadin_seconds");
+        }
+        if old.ical_export_path != new.ical_export_path {
+            self.ical_export_path = new.ical_export_path.clone();
+            applied.push("ical_export_path");
+        }
+        if old.auto_start_breaks != new.auto_start_breaks {
+            self.auto_start_breaks = new.auto_start_breaks;
+            applied.push("auto_start_breaks");
+        }
+        if old.auto_start_work != new.auto_start_work {
+            self.auto_start_work = new.auto_start_work;
+            applied.push("auto_start_work");
+        }
+        if old.overtime != new.overtime {
+            self.overtime = new.overtime;
+            applied.push("overtime");
+        }
+        if old.ask_skip_reason != new.ask_skip_reason || old.skip_reasons != new.skip_reasons {
+            self.ask_skip_reason = new.ask_skip_reason;
+            self.skip_reasons = new.skip_reasons.clone();
+            applied.push("skip_reasons");
+        }
+        if old.max_content_width != new.max_content_width || old.side_panels != new.side_panels {
+            self.max_content_width = new.max_content_width;
+            self.side_panels = new.side_panels;
+            applied.push("layout");
+        }
+        if old.smooth_progress != new.smooth_progress {
+            self.smooth_progress = new.smooth_progress;
+            applied.push("smooth_progress");
+        }
+        if old.ask_next_action != new.ask_next_action {
+            self.ask_next_action = new.ask_next_action;
+            applied.push("ask_next_action");
+        }
+        
+        // These shape the run itself, so changing them mid-run would leave
+        // the session counts and durations inconsistent
+        if old.limits != new.limits {
+            deferred.push("limits");
+        }
+        let durations = |c: &Config| {
+            (c.work_minutes, c.short_break_minutes, c.long_break_minutes, c.total_sessions, c.long_break_interval)
+        };
+        if durations(old) != durations(new) {
+            deferred.push("durations");
+        }
+        if old.long_break_cycle != new.long_break_cycle {
+            deferred.push("long_break_cycle");
+        }
+        if old.count_skipped_work != new.count_skipped_work {
+            deferred.push("count_skipped_work");
+        }
+        if old.long_break_needs_completed != new.long_break_needs_completed {
+            deferred.push("long_break_needs_completed");
+        }
+        if old.todo_file != new.todo_file {
+            deferred.push("todo_file");
+        }
+        if old.ritual != new.ritual {
+            deferred.push("ritual");
+        }
+        (applied, deferred)
+    }
+
+    // Stays on the setup screen, with the bad field focused, on invalid input
+    pub fn start_timer(&mut self) {
+        // Until the first start these hold the config's values
+        let durations = [self.work_duration, self.short_break_duration, self.long_break_duration];
+        let counts = [self.total_sessions, self.long_break_interval];
+        let mut duration_values = [Duration::ZERO; 3];
+        let mut count_values = [0; 2];
+        for (i, default) in durations.into_iter().enumerate() {
+            match self.inputs[i].get_duration(default) {
+                Ok(value) => duration_values[i] = value,
+                Err(e) => return self.reject_field(i, e),
+            }
+        }
+        for (i, default) in counts.into_iter().enumerate() {
+            match self.inputs[i + 3].get_value(default) {
+                Ok(value) => count_values[i] = value,
+                Err(e) => return self.reject_field(i + 3, e),
+            }
+        }
+        let volume = match self.inputs[5].get_value(volume_percent(self.volume)) {
+            Ok(value) => value,
+            Err(e) => return self.reject_field(5, e),
+        };
+        [self.work_duration, self.short_break_duration, self.long_break_duration] = duration_values;
+        [self.total_sessions, self.long_break_interval] = count_values;
+        self.volume = volume as f32 / 100.0;
+        self.setup_error = None;
+        
+        self.current_task = self.todos.get(self.todo_index).cloned();
+        self.task_label = self.label_input.value.trim().to_string();
+        self.current_session = 1;
+        self.run_elapsed = Duration::ZERO;
+        self.completed_work_sessions = 0;
+        self.work_since_long_break = 0;
+        self.focus_time = Duration::ZERO;
+        if self.ritual.is_empty() {
+            self.begin(TimerType::Work);
+        } else {
+            self.ritual_index = 0;
+            self.state = AppState::Ritual;
+        }
+    }
+    
+    // Straight back into a timer left by a quit or crash, with `remaining`
+    // to go. The run's own durations come back with it
+    pub fn resume(&mut self, session: &SessionState, remaining: Duration) {
+        self.work_duration = Duration::from_secs(session.work_secs);
+        self.short_break_duration = Duration::from_secs(session.short_break_secs);
+        self.long_break_duration = Duration::from_secs(session.long_break_secs);
+        self.total_sessions = session.total_sessions;
+        self.current_session = session.current_session;
+        if let Some(interval) = session.long_break_interval {
+            self.long_break_interval = interval;
+        }
+        self.work_since_long_break = session.work_since_long_break;
+        self.completed_work_sessions = session.completed_work_sessions;
+        self.long_breaks_taken = session.long_breaks_taken;
+        self.current_task = self.todos.get(self.todo_index).cloned();
+        self.begin(session.timer_type);
+        // A long break was counted as taken when it started
+        if session.timer_type == TimerType::LongBreak {
+            self.session_total = self.long_break_for(self.long_breaks_taken.saturating_sub(1));
+        }
+        self.time_left = remaining.min(self.session_total);
+        if session.paused {
+            self.paused = true;
+            self.paused_at = Some(Instant::now());
+        }
+    }
+    
+    // What's wrong with a setup field, checked the way `start_timer` will;
+    // a blank field takes its default and is always fine
+    pub fn field_error(&self, index: usize) -> Option<String> {
+        let input = self.inputs.get(index)?;
+        if index < 3 {
+            input.get_duration(Duration::ZERO).err()
+        } else {
+            input.get_value(0).err()
+        }
+    }
+    
+    pub fn reject_field(&mut self, index: usize, error: String) {
+        self.setup_error = Some(format!("{}: {}", FIELD_LABELS[index].0, error));
+        self.set_focus(index);
+    }
+    
+    pub fn open_stats(&mut self) {
+        self.stats = stats::summarize(&history::load_records(), self.clock.now().date_naive());
+        self.state = AppState::Stats;
+    }
+    
+    // Acknowledges the current ritual step; the first session starts
+    // after the last one
+    pub fn next_ritual_step(&mut self) {
+        self.ritual_index += 1;
+        if self.ritual_index >= self.ritual.len() {
+            self.begin(TimerType::Work);
+        }
+    }
+    
+    pub fn clock_hidden(&self) -> bool {
+        self.hide_clock
+            && self.timer_type == TimerType::Work
+            && !self.timer_held()
+            && self.reveal_until.is_none_or(|until| Instant::now() >= until)
+    }
+    
+    // A configured override wins over the generated line; either is cut
+    // down to the terminal width so it never wraps
+    pub fn help_text(&self, screen: &str, default: impl FnOnce() -> String) -> String {
+        let help = self.help_overrides.get(screen).cloned().unwrap_or_else(default);
+        truncate(&help, self.content_width() as usize)
+    }
+    
+    // Width available to centered text, see `max_content_width`
+    pub fn content_width(&self) -> u16 {
+        match self.max_content_width {
+            0 => self.width,
+            max => self.width.min(max),
+        }
+    }
+    
+    // Columns on each side of the content, when wide enough for side panels
+    pub fn side_panel_width(&self) -> Option<u16> {
+        let margin = (self.width - self.content_width()) / 2;
+        (self.side_panels && margin >= MIN_PANEL_WIDTH).then_some(margin)
+    }
+    
+    // Left panel: how far through the run; right panel: what comes next
+    // and how this session has gone
+    pub fn side_panel_lines(&self) -> (Vec<String>, Vec<String>) {
+        let done = match self.timer_type {
+            TimerType::Work => self.current_session - 1,
+            _ => self.current_session,
+        };
+        let progress = (self.timer_type == TimerType::Work).then(|| self.timer_progress());
+        let left = vec![
+            "SESSIONS".to_string(),
+            session_dots(self.current_session, self.total_sessions, progress),
+            format!("{}/{} done", done, self.total_sessions),
+            String::new(),
+            "RUN".to_string(),
+            format!("{:.0}% complete", self.run_progress() * 100.0),
+        ];
+        
+        let next = match self.timer_type {
+            TimerType::Work if self.current_session >= self.total_sessions => "All done".to_string(),
+            TimerType::Work => match self.break_after_work(true) {
+                TimerType::LongBreak => format!("Long break, {}", length_label(self.duration_for(TimerType::LongBreak))),
+                break_type => format!("Short break, {}", length_label(self.duration_for(break_type))),
+            },
+            _ => format!("Work session {}, {}", self.current_session + 1, length_label(self.work_duration)),
+        };
+        let right = vec![
+            "NEXT UP".to_string(),
+            next,
+            String::new(),
+            "THIS SESSION".to_string(),
+            format!("{} pause{}", self.pause_count, if self.pause_count == 1 { "" } else { "s" }),
+            format!("{} interruption{}", self.interruptions, if self.interruptions == 1 { "" } else { "s" }),
+        ];
+        (left, right)
+    }
+    
+    pub fn cycle_theme(&mut self) {
+        let next = (theme::theme_index(self.theme.name) + 1) % THEMES.len();
+        self.theme = THEMES[next].with_colors(&self.theme_colors);
+        self.command_message = Some(format!("Theme: {}", self.theme.name));
+    }
+    
+    // One VOLUME_STEP louder or quieter, kept to whole steps so repeated
+    // presses don't drift
+    pub fn nudge_volume(&mut self, louder: bool) {
+        let step = if louder { VOLUME_STEP } else { -VOLUME_STEP };
+        self.volume = ((self.volume + step).clamp(0.0, 1.0) * 10.0).round() / 10.0;
+        self.command_message = Some(format!("Volume {}%", volume_percent(self.volume)));
+    }
+    
+    pub fn save_theme(&mut self) {
+        self.command_message = Some(match config::save_setting("theme", self.theme.name) {
+            Ok(()) => format!("Theme '{}' saved to config", self.theme.name),
+            Err(e) => format!("Could not save theme: {}", e),
+        });
+    }
+    
+    pub fn session_state(&self) -> SessionState {
+        SessionState {
+            timer_type: self.timer_type,
+            current_session: self.current_session,
+            total_sessions: self.total_sessions,
+            remaining_secs: self.secs_left(),
+            work_secs: self.work_duration.as_secs(),
+            short_break_secs: self.short_break_duration.as_secs(),
+            long_break_secs: self.long_break_duration.as_secs(),
+            paused: self.paused,
+            long_break_interval: Some(self.long_break_interval),
+            work_since_long_break: self.work_since_long_break,
+            completed_work_sessions: self.completed_work_sessions,
+            long_breaks_taken: self.long_breaks_taken,
+        }
+    }
+    
+    // Snapshot for `--status`, as of `now`
+    pub fn live_status(&self, now: Instant) -> LiveStatus {
+        LiveStatus {
+            timer_type: self.timer_type,
+            current_session: self.current_session,
+            total_sessions: self.total_sessions,
+            remaining_secs: self.secs_left(),
+            overtime_secs: (self.state == AppState::Overtime).then(|| self.overtime_at(now).as_secs()),
+            paused: self.paused,
+            updated: Local::now().timestamp(),
+        }
+    }
+    
+    pub fn deferred(&self) -> bool {
+        self.deferred_until.is_some()
+    }
+    
+    pub fn defer(&mut self, duration: Duration) -> String {
+        self.deferred_until = Some(Instant::now() + duration);
+        format!("Deferred for {} min, timer and alerts on hold", duration.as_secs() / 60)
+    }
+    
+    pub fn cancel_defer(&mut self) -> String {
+        self.deferred_until = None;
+        "Deferral cancelled".to_string()
+    }
+    
+    pub fn toggle_defer(&mut self) {
+        self.command_message = Some(if self.deferred() {
+            self.cancel_defer()
+        } else {
+            self.defer(self.defer_duration)
+        });
+    }
+    
+    // Pick the timer back up once the meeting is over
+    pub fn check_defer(&mut self) {
+        if self.deferred_until.is_some_and(|until| Instant::now() >= until) {
+            self.deferred_until = None;
+            self.command_message = Some("Deferral over, timer resumed".to_string());
+            self.notifier.notify("Pomodoro", "Deferral over, timer resumed.");
+        }
+    }
+    
+    pub fn check_auto_resume(&mut self) {
+        let expired = self.paused_at.is_some_and(|start| start.elapsed() >= self.auto_resume_after);
+        if self.paused && !self.idle_paused && !self.deferred() && !self.auto_resume_after.is_zero() && expired {
+            self.toggle_pause();
+            self.notifier.notify("Pomodoro", "Timer resumed after a long pause.");
+        }
+    }
+    
+    // "ends at 14:52" in local time, or "ends at —" while the countdown
+    // stands still and the finish can't be known
+    pub fn ends_at(&self, now: DateTime<Local>) -> String {
+        if !self.counting() {
+            return "ends at —".to_string();
+        }
+        let end = now + chrono::Duration::seconds(self.secs_left() as i64);
+        format!("ends at {}", end.format("%H:%M"))
+    }
+    
+    // Work that has gone on for auto_pause_idle with no key pressed pauses
+    // itself. The session must have run that long too, so one started
+    // without a key (after a break, say) isn't paused straight away
+    pub fn check_idle_at(&mut self, now: Instant) {
+        let idle = now.saturating_duration_since(self.last_input);
+        let ran = self.session_total.saturating_sub(self.time_left);
+        let limit = self.auto_pause_idle;
+        if !limit.is_zero() && self.timer_type == TimerType::Work && self.counting() && idle >= limit && ran >= limit {
+            self.toggle_pause_at(now);
+            self.idle_paused = true;
+        }
+    }
+    
+    // Called for every key. True if it only ended an idle pause, in which
+    // case it shouldn't do anything else
+    pub fn note_key(&mut self, now: Instant) -> bool {
+        self.last_input = now;
+        let woke = self.idle_paused && self.paused;
+        if woke {
+            self.toggle_pause_at(now);
+        }
+        self.idle_paused = false;
+        woke
+    }
+    
+    // Includes a pause that is still in progress
+    pub fn total_paused(&self) -> Duration {
+        self.paused_total + self.paused_at.map_or(Duration::ZERO, |start| start.elapsed())
+    }
+    
+    // Durations changed here only apply from the next session on; the
+    // current countdown and already logged sessions are left alone
+    pub fn run_command(&mut self, command: &str) -> Result<String, String> {
+        let minutes = |word: &str| {
+            word.parse::<u64>()
+                .ok()
+                .filter(|m| *m > 0)
+                .map(|m| Duration::from_secs(m * 60))
+                .ok_or_else(|| format!("Invalid minutes: {}", word))
+        };
+        
+        match command.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["defer"] => return Ok(self.defer(self.defer_duration)),
+            ["defer", "off"] => return Ok(self.cancel_defer()),
+            ["defer", m] => {
+                return Ok(self.defer(minutes(m.trim_end_matches('m'))?));
+            }
+            ["work", m] => self.work_duration = minutes(m)?,
+            ["short", m] => self.short_break_duration = minutes(m)?,
+            // A fixed long break replaces the configured cycle
+            ["long", m] => {
+                self.long_break_duration = minutes(m)?;
+                self.long_break_cycle.clear();
+            }
+            ["preset", w, s, l] => {
+                let (w, s, l) = (minutes(w)?, minutes(s)?, minutes(l)?);
+                self.work_duration = w;
+                self.short_break_duration = s;
+                self.long_break_duration = l;
+                self.long_break_cycle.clear();
+            }
+            _ => return Err(format!("Unknown command: {}", command.trim())),
+        }
+        
+        Ok(format!(
+            "Next sessions: {} work / {} short / {} long",
+            length_label(self.work_duration),
+            length_label(self.short_break_duration),
+            length_label(self.duration_for(TimerType::LongBreak))
+        ))
+    }
+    
+    pub fn command_key(&mut self, code: Key) {
+        let Some(line) = self.command_line.as_mut() else {
+            return;
+        };
+        match code {
+            Key::Esc => self.command_line = None,
+            Key::Enter => {
+                let command = self.command_line.take().unwrap_or_default();
+                self.command_message = Some(match self.run_command(&command) {
+                    Ok(message) | Err(message) => message,
+                });
+            }
+            Key::Backspace => {
+                line.pop();
+            }
+            Key::Char(c) => line.push(c),
+            _ => {}
+        }
+    }
+
+    pub fn open_label_edit(&mut self) {
+        self.label_edit = Some(self.task_label.clone());
+        self.command_message = None;
+    }
+    
+    // The countdown keeps going while the label is typed
+    pub fn label_edit_key(&mut self, code: Key) {
+        let Some(label) = self.label_edit.as_mut() else {
+            return;
+        };
+        match code {
+            Key::Esc => self.label_edit = None,
+            Key::Enter => {
+                self.task_label = self.label_edit.take().unwrap_or_default().trim().to_string();
+            }
+            Key::Backspace => {
+                label.pop();
+            }
+            Key::Char(c) if label.chars().count() < LABEL_MAX_CHARS => label.push(c),
+            _ => {}
+        }
+    }
+    
+    // Typing in the command line or a new time holds the countdown
+    pub fn timer_held(&self) -> bool {
+        self.command_line.is_some() || self.time_edit.is_some()
+    }
+    
+    // Up to the setup limit for the kind of timer that is running
+    pub fn open_time_edit(&mut self) {
+        let max = match self.timer_type {
+            TimerType::Work => self.inputs[0].max,
+            TimerType::ShortBreak => self.inputs[1].max,
+            TimerType::LongBreak => self.inputs[2].max,
+        };
+        let mut field = InputField::new("MM:SS", (0, max));
+        field.focused = true;
+        self.time_edit = Some(field);
+        self.command_message = None;
+    }
+    
+    pub fn time_edit_key(&mut self, code: Key) {
+        let Some(field) = self.time_edit.as_mut() else {
+            return;
+        };
+        match code {
+            Key::Esc => self.time_edit = None,
+            Key::Enter => match field.get_time() {
+                Some(time) => {
+                    // The session grows or shrinks by as much, like with
+                    // +1m/-1m, so time already done stays done
+                    if time > self.time_left {
+                        self.session_total += time - self.time_left;
+                    } else {
+                        self.session_total = self.session_total.saturating_sub(self.time_left - time);
+                    }
+                    self.time_left = time;
+                    self.command_message = Some(format!("Remaining time set to {}:{:02}", time.as_secs() / 60, time.as_secs() % 60));
+                    self.time_edit = None;
+                }
+                None => {
+                    self.command_message = Some(format!("Invalid time, use MM:SS up to {}:00", field.max));
+                }
+            },
+            Key::Backspace => {
+                field.value.pop();
+            }
+            Key::Char(c) => field.push_time_char(c),
+            _ => {}
+        }
+    }
+    
+    // Setup focus cycles through the inputs, then the task picker if any
+    pub fn focus_count(&self) -> usize {
+        self.inputs.len() + 1 + usize::from(!self.todos.is_empty())
+    }
+    
+    pub fn label_focused(&self) -> bool {
+        self.focus_index == self.inputs.len()
+    }
+    
+    pub fn task_picker_focused(&self) -> bool {
+        self.focus_index == self.inputs.len() + 1
+    }
+    
+    pub fn set_focus(&mut self, index: usize) {
+        self.focus_index = index;
+        for (i, input) in self.inputs.iter_mut().enumerate() {
+            input.focused = i == index;
+        }
+        self.label_input.focused = self.label_focused();
+    }
+    
+    pub fn cycle_task(&mut self, forward: bool) {
+        let count = self.todos.len();
+        if count > 0 {
+            self.todo_index = if forward {
+                (self.todo_index + 1) % count
+            } else {
+                (self.todo_index + count - 1) % count
+            };
+        }
+    }
+    
+    // True if the app can quit right away. A running session asks first,
+    // unless confirm_quit is off
+    pub fn request_quit(&mut self) -> bool {
+        let running = matches!(self.state, AppState::Running | AppState::Overtime);
+        self.quit_pending = self.confirm_quit && running;
+        !self.quit_pending
+    }
+    
+    // The answer to "Quit? [y/N]": only `y` quits
+    pub fn quit_prompt_key(&mut self, key: Key) -> bool {
+        self.quit_pending = false;
+        matches!(key, Key::Char('y' | 'Y'))
+    }
+    
+    // Fills the first four fields from the next or previous preset; any of
+    // them can still be edited afterwards
+    pub fn cycle_preset(&mut self, forward: bool) {
+        let count = self.presets.len();
+        if count == 0 {
+            return;
+        }
+        let index = match (self.preset_index, forward) {
+            (Some(i), true) => (i + 1) % count,
+            (Some(i), false) => (i + count - 1) % count,
+            (None, true) => 0,
+            (None, false) => count - 1,
+        };
+        self.preset_index = Some(index);
+        for (input, value) in self.inputs.iter_mut().zip(self.presets[index].values()) {
+            input.value = value.to_string();
+        }
+        self.setup_error = None;
+    }
+    
+    // "Deep Work", "Deep Work (edited)" once a field is changed, or "Custom"
+    pub fn preset_label(&self) -> String {
+        let Some(preset) = self.preset_index.and_then(|i| self.presets.get(i)) else {
+            return "Custom".to_string();
+        };
+        // A blank field falls back to the config default in its placeholder
+        let edited = self.inputs.iter().zip(preset.values()).any(|(input, value)| {
+            let text = if input.value.is_empty() { &input.placeholder } else { &input.value };
+            *text != value.to_string()
+        });
+        if edited {
+            format!("{} (edited)", preset.name)
+        } else {
+            preset.name.clone()
+        }
+    }
+    
+    pub fn mark_task_done(&mut self) {
+        if let (Some(path), Some(task)) = (&self.todo_path, self.current_task.take()) {
+            if todo::mark_done(path, &task).is_ok() {
+                self.todos.retain(|item| item != &task);
+                self.todo_index = 0;
+                self.notifier.notify("Pomodoro", &format!("Task done: {}", task.text));
+            } else {
+                self.current_task = Some(task);
+            }
+        }
+    }
+    
+    pub fn log_work_session(&mut self, entry: &HistoryEntry) {
+        let _ = history::append_record(entry);
+        if let Some(path) = self.ical_export_path.as_deref().filter(|_| !entry.skipped) {
+            let _ = ical::append_event(Path::new(path), entry);
+        }
+        if !entry.skipped {
+            self.count_toward_goal(entry.timestamp.date_naive());
+            self.stats = stats::summarize(&history::load_records(), entry.timestamp.date_naive());
+        }
+    }
+    
+    // One more pomodoro done on `day`; a new day starts the count over
+    pub fn count_toward_goal(&mut self, day: NaiveDate) {
+        if day != self.goal_day {
+            self.goal_day = day;
+            self.done_today = 0;
+            self.goal_announced = false;
+        }
+        self.done_today += 1;
+        let goal = self.daily_goal as usize;
+        if goal > 0 && self.done_today >= goal && !self.goal_announced {
+            self.goal_announced = true;
+            self.notifier.notify("Pomodoro", &format!("Daily goal reached: {} pomodoros today! 🏆", self.done_today));
+        }
+    }
+    
+    // "3/8 today", or None without a goal
+    pub fn goal_progress(&self, today: NaiveDate) -> Option<String> {
+        if self.daily_goal == 0 {
+            return None;
+        }
+        let done = if today == self.goal_day { self.done_today } else { 0 };
+        Some(format!("{}/{} today", done, self.daily_goal))
+    }
+    
+    // Carries out what the timer logic asked for; true once the run is over
+    pub fn perform(&mut self, effects: Vec<Effect>) -> bool {
+        let mut finished = false;
+        for effect in effects {
+            // Ticks come every second; the log only needs the transitions
+            if effect != Effect::Tick {
+                log::debug(&format!("{:?} (state={:?}, type={:?})", effect, self.state, self.timer_type));
+            }
+            match effect {
+                Effect::Notify(message) => self.notifier.notify("Pomodoro", &message),
+                Effect::PlaySound => self.completion_alert(),
+                Effect::Tick => self.countdown_tick(),
+                Effect::Log(entry) => self.log_work_session(&entry),
+                Effect::SkipReason(reason) => record_skip_reason(reason),
+                Effect::Exit => finished = true,
+            }
+        }
+        finished
+    }
+    
+    // Sound plus optional vibration, both silenced by mute
+    pub fn completion_alert(&mut self) {
+        if self.muted || self.deferred() {
+            return;
+        }
+        let alert = Alert {
+            track: self.playlist.pick(),
+            volume: self.volume,
+            vibrate: self.vibrate,
+            vibrate_command: self.vibrate_command.clone(),
+        };
+        self.alerts.alert(&self.audio, alert);
+    }
+    
+    pub fn countdown_tick(&self) {
+        let secs_left = self.secs_left();
+        if self.muted || self.no_tick || secs_left == 0 || secs_left > self.countdown_secs() {
+            return;
+        }
+        match &self.tick_sound {
+            Some(path) => self.audio.play_tick_sound(path.clone(), self.volume),
+            None => self.audio.play_tick(tone::tick_for(self.countdown_pattern, secs_left, self.countdown_ticks), self.volume),
+        }
+    }
+    
+    // Seconds at the end of each timer that tick
+    pub fn countdown_secs(&self) -> u64 {
+        match self.countdown_ticks {
+            0 if self.tick_sound.is_some() => TICK_SOUND_SECS,
+            secs => secs,
+        }
+    }
+    
+    // Opens the reason picker instead of skipping right away when enabled,
+    // in which case there is nothing to do yet
+    pub fn request_skip_break(&mut self) -> Vec<Effect> {
+        if self.ask_skip_reason && !self.skip_reasons.is_empty() {
+            self.skip_reason_choice = Some(0);
+            return Vec::new();
+        }
+        self.skip_break()
+    }
+    
+    // Some once the break has been skipped. Escape skips without picking
+    // and records NO_SKIP_REASON
+    pub fn skip_reason_key(&mut self, code: Key) -> Option<Vec<Effect>> {
+        let choice = self.skip_reason_choice?;
+        let count = self.skip_reasons.len();
+        let reason = match code {
+            Key::Up | Key::Char('k') => {
+                self.skip_reason_choice = Some((choice + count - 1) % count);
+                return None;
+            }
+            Key::Down | Key::Char('j') => {
+                self.skip_reason_choice = Some((choice + 1) % count);
+                return None;
+            }
+            Key::Enter => self.skip_reasons.get(choice)?.clone(),
+            Key::Esc => NO_SKIP_REASON.to_string(),
+            Key::Char(c) => {
+                let index = c.to_digit(10)?.checked_sub(1)?;
+                self.skip_reasons.get(index as usize)?.clone()
+            }
+            _ => return None,
+        };
+        self.skip_reason_choice = None;
+        let mut effects = vec![Effect::SkipReason(reason)];
+        effects.extend(self.skip_break());
+        Some(effects)
+    }
+    
+    // A key binding's action in the current state; actions that don't
+    // apply to it are ignored, and so is Quit, which is the caller's to
+    // handle. Only the timer changes here, the returned effects are left
+    // to the caller
+    pub fn input(&mut self, action: Action) -> Vec<Effect> {
+        match (self.state, action) {
+            (AppState::Setup, Action::FocusNext) => self.set_focus((self.focus_index + 1) % self.focus_count()),
+            (AppState::Setup, Action::FocusPrev) => {
+                let count = self.focus_count();
+                self.set_focus((self.focus_index + count - 1) % count);
+            }
+            (AppState::Setup, Action::Start) => self.start_timer(),
+            (AppState::Setup, Action::OpenStats) => self.open_stats(),
+            (AppState::Setup, Action::ToggleAutoStart) => self.auto_start_breaks = !self.auto_start_breaks,
+            (AppState::Setup, Action::PrevChoice) if self.task_picker_focused() => self.cycle_task(false),
+            (AppState::Setup, Action::NextChoice) if self.task_picker_focused() => self.cycle_task(true),
+            (AppState::Setup, Action::PrevChoice) => self.cycle_preset(false),
+            (AppState::Setup, Action::NextChoice) => self.cycle_preset(true),
+            (AppState::WorkPrompt, Action::StartWork) => self.begin(TimerType::Work),
+            (AppState::Stats, Action::Back) => self.state = AppState::Setup,
+            (AppState::Ritual, Action::NextStep) => self.next_ritual_step(),
+            (AppState::Ritual, Action::SkipRitual) => self.begin(TimerType::Work),
+            (AppState::BreakPrompt, Action::StartBreak) => self.confirm_break(),
+            // The start key again cuts the lead-in short
+            (AppState::BreakLeadIn, Action::StartBreak) => self.start_break(),
+            (AppState::BreakPrompt | AppState::BreakLeadIn, Action::SkipBreak) => return self.request_skip_break(),
+            (AppState::Running | AppState::BreakPrompt | AppState::BreakLeadIn, Action::ToggleMute) => self.muted = !self.muted,
+            (AppState::Running, Action::Skip) => return self.skip_at(Instant::now()),
+            (AppState::Overtime, Action::Skip) => {
+                self.end_overtime(Instant::now());
+                return self.advance_timer();
+            }
+            (AppState::Overtime, Action::SkipToWork) => {
+                self.end_overtime(Instant::now());
+                return self.skip_to_work();
+            }
+            (AppState::Running, Action::SkipToWork) => return self.skip_to_work(),
+            (AppState::Running, Action::TogglePause) => self.toggle_pause(),
+            (AppState::Running, Action::AddMinute) => self.add_minute(),
+            (AppState::Running, Action::SubMinute) => self.sub_minute(),
+            (AppState::Running, Action::Restart) => self.restart_timer(),
+            (AppState::Running, Action::VolumeUp) => self.nudge_volume(true),
+            (AppState::Running, Action::VolumeDown) => self.nudge_volume(false),
+            (AppState::Running, Action::Interruption) => self.interruptions += 1,
+            (AppState::Running, Action::RunProgress) => self.show_run_progress = !self.show_run_progress,
+            (AppState::Running, Action::Defer) => self.toggle_defer(),
+            (AppState::Running, Action::EditTime) => self.open_time_edit(),
+            (AppState::Running, Action::EditLabel) => self.open_label_edit(),
+            (AppState::Running, Action::CycleTheme) => self.cycle_theme(),
+            (AppState::Running, Action::SaveTheme) => self.save_theme(),
+            (AppState::Running, Action::TaskDone) => self.mark_task_done(),
+            (AppState::Running, Action::Command) => {
+                self.command_line = Some(String::new());
+                self.command_message = None;
+            }
+            _ => {}
+        }
+        Vec::new()
+    }
+    
+    // Typing into the focused setup field. The durations also take "MM:SS"
+    pub fn setup_text_key(&mut self, code: Key) {
+        let is_duration = self.focus_index < 3;
+        match code {
+            Key::Char(_) if self.label_focused() && self.label_input.value.chars().count() >= LABEL_MAX_CHARS => {}
+            Key::Char(c) if self.label_focused() => self.label_input.value.push(c),
+            Key::Backspace if self.label_focused() => {
+                self.label_input.value.pop();
+            }
+            Key::Char(c) if c.is_ascii_digit() || c == ':' => {
+                if let Some(input) = self.inputs.get_mut(self.focus_index) {
+                    if is_duration {
+                        input.push_time_char(c);
+                    } else if c != ':' {
+                        input.push_digit(c);
+                    }
+                }
+            }
+            Key::Backspace => {
+                if let Some(input) = self.inputs.get_mut(self.focus_index) {
+                    input.value.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    pub fn status(&self) -> Status {
+        Status {
+            state: self.state,
+            timer_type: self.timer_type,
+            secs_left: self.secs_left(),
+            session: self.current_session,
+            total_sessions: self.total_sessions,
+            paused: self.paused,
+            label: Some(self.task_label.clone()).filter(|label| !label.is_empty()),
+        }
+    }
+    
+}
+
+pub fn volume_percent(volume: f32) -> u32 {
+    (volume.clamp(0.0, 1.0) * 100.0).round() as u32
+}
+
+pub fn load_art(path: &str) -> Option<Vec<String>> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let mut lines: Vec<String> = text.lines().map(|line| line.trim_end().to_string()).collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    Some(lines).filter(|lines| !lines.is_empty())
+}
+
+// "25 min", or "1:30" when there are seconds
+pub fn length_label(length: Duration) -> String {
+    let secs = length.as_secs();
+    if secs.is_multiple_of(60) {
+        format!("{} min", secs / 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+// Eighths of a block, for the partly done session
+pub const DOT_FRACTIONS: [char; 8] = ['░', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+// Past this many sessions the dots give way to "2/24"
+pub const MAX_SESSION_DOTS: u32 = 20;
+
+// How a session dot is drawn: done plain, the one in progress in the
+// timer's color, the rest dim
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DotState {
+    Done,
+    Current,
+    Upcoming,
+}
+
+// One dot per work session: solid for finished sessions, a partial block
+// for the one in progress and shaded for the ones still to come. A
+// `progress` of None means a break, after which the session is done. None
+// past MAX_SESSION_DOTS
+pub fn session_dot_cells(current: u32, total: u32, progress: Option<f64>) -> Option<Vec<(char, DotState)>> {
+    if total > MAX_SESSION_DOTS {
+        return None;
+    }
+    let cells = (1..=total)
+        .map(|session| {
+            if session < current || (session == current && progress.is_none()) {
+                ('█', DotState::Done)
+            } else if session == current {
+                let eighths = (progress.unwrap_or(0.0).clamp(0.0, 1.0) * 8.0) as usize;
+                (DOT_FRACTIONS[eighths.min(7)], DotState::Current)
+            } else {
+                ('░', DotState::Upcoming)
+            }
+        })
+        .collect();
+    Some(cells)
+}
+
+// "█ ▌ ░ ░", or "2/24" for long runs
+pub fn session_dots(current: u32, total: u32, progress: Option<f64>) -> String {
+    match session_dot_cells(current, total, progress) {
+        Some(cells) => cells.iter().map(|(dot, _)| dot.to_string()).collect::<Vec<_>>().join(" "),
+        None => format!("{}/{}", current, total),
+    }
+}
+
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        let mut short: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+        short.push('…');
+        short
+    }
+}
+
+pub fn vibrate(command: Option<&str>) {
+    use std::process::{Command, Stdio};
+    
+    // A helper command (e.g. `termux-vibrate -d 600`) wins everywhere
+    if let Some(command) = command {
+        #[cfg(target_os = "windows")]
+        let mut shell = Command::new("cmd");
+        #[cfg(target_os = "windows")]
+        shell.args(["/C", command]);
+        
+        #[cfg(not(target_os = "windows"))]
+        let mut shell = Command::new("sh");
+        #[cfg(not(target_os = "windows"))]
+        shell.args(["-c", command]);
+        
+        let _ = shell.stdout(Stdio::null()).stderr(Stdio::null()).spawn();
+        return;
+    }
+    
+    #[cfg(target_os = "android")]
+    {
+        let _ = Command::new("termux-vibrate")
+            .args(["-d", "600"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+    }
+    
+    // No vibration motor to drive: ring the terminal bell in a short pattern
+    #[cfg(not(target_os = "android"))]
+    {
+        std::thread::spawn(|| {
+            for _ in 0..3 {
+                let mut stdout = io::stdout();
+                let _ = stdout.write_all(b"\x07");
+                let _ = stdout.flush();
+                std::thread::sleep(Duration::from_millis(250));
+            }
+        });
+    }
+}
+
+// Attached to the work session the skipped break followed, which is the
+// last one logged
+pub fn record_skip_reason(reason: String) {
+    let Some(mut entry) = history::load_records().pop() else {
+        return;
+    };
+    entry.break_skip_reason = Some(reason);
+    let _ = history::replace_last_record(&entry);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn task_label_is_saved_with_the_session() {
+        let mut app = PomodoroApp::new(&Config::default());
+        app.label_input.value = " client A ".to_string();
+        app.start_timer();
+        assert_eq!(app.work_entry().label.as_deref(), Some("client A"));
+        
+        app.open_label_edit();
+        for _ in 0..8 {
+            app.label_edit_key(Key::Backspace);
+        }
+        app.label_edit_key(Key::Enter);
+        assert_eq!(app.label_edit, None);
+        assert_eq!(app.work_entry().label, None);
+    }
+
+    #[test]
+    fn input_drives_the_timer_without_a_terminal() {
+        let mut app = PomodoroApp::new(&Config::default());
+        app.start_timer();
+        assert!(app.input(Action::TogglePause).is_empty());
+        assert!(app.status().paused);
+        // Prompt actions do nothing while the timer runs
+        assert!(app.input(Action::StartBreak).is_empty());
+        assert_eq!(app.status().state, AppState::Running);
+        
+        let effects = app.input(Action::Skip);
+        assert!(effects.iter().any(|effect| matches!(effect, Effect::Log(entry) if entry.skipped)));
+        assert_eq!(app.status().state, AppState::BreakPrompt);
+        app.input(Action::ToggleMute);
+        assert!(app.muted);
+        app.input(Action::StartBreak);
+        let status = app.status();
+        assert_eq!((status.state, status.timer_type), (AppState::Running, TimerType::ShortBreak));
+        assert_eq!(status.secs_left, 5 * 60);
+    }
+
+    #[test]
+    fn setup_keys_go_through_actions() {
+        let mut app = PomodoroApp::new(&Config::default());
+        let mut press = |key| {
+            let action = app.keymap.map_key(app.state, key);
+            match action {
+                Some(action) => app.input(action),
+                None => {
+                    app.setup_text_key(key);
+                    Vec::new()
+                }
+            }
+        };
+        press(Key::Backspace);
+        press(Key::Backspace);
+        press(Key::Char('1'));
+        press(Key::Up);
+        press(Key::Down);
+        press(Key::Enter);
+        assert_eq!(app.state, AppState::Running);
+        assert_eq!(app.time_left, Duration::from_secs(60));
+    }
+
+    #[test]
+    fn volume_moves_in_whole_steps_within_range() {
+        let mut app = PomodoroApp::new(&Config { volume: 0.95, ..Config::default() });
+        app.nudge_volume(true);
+        assert_eq!(app.volume, 1.0);
+        for _ in 0..3 {
+            app.nudge_volume(false);
+        }
+        assert_eq!(volume_percent(app.volume), 70);
+        assert_eq!(app.command_message.as_deref(), Some("Volume 70%"));
+        
+        app.inputs[5].value = "40".to_string();
+        app.start_timer();
+        assert_eq!(volume_percent(app.volume), 40);
+    }
+
+    #[test]
+    fn tick_sound_covers_last_ten_seconds_by_default() {
+        let config = Config { tick_sound: Some("/tmp/tick.wav".to_string()), ..Config::default() };
+        assert_eq!(PomodoroApp::new(&config).countdown_secs(), TICK_SOUND_SECS);
+        assert_eq!(PomodoroApp::new(&Config { countdown_ticks: 3, ..config }).countdown_secs(), 3);
+        assert_eq!(PomodoroApp::new(&Config::default()).countdown_secs(), 0);
+    }
+
+    #[test]
+    fn finished_work_session_notifies_once() {
+        let mut app = PomodoroApp::new(&Config::default());
+        let notifier = notifier::RecordingNotifier::default();
+        app.notifier = Box::new(notifier.clone());
+        app.start_timer();
+        app.time_left = Duration::from_secs(1);
+        let mut effects = app.tick();
+        // Keeps the history file out of it
+        effects.retain(|effect| !matches!(effect, Effect::Log(_)));
+        app.perform(effects);
+        assert_eq!(
+            notifier.sent(),
+            [("Pomodoro".to_string(), "Work session finished! Time for a short break.".to_string())]
+        );
+    }
+
+    #[test]
+    fn edited_time_carries_into_the_session_length() {
+        let mut app = PomodoroApp::new(&Config::default());
+        app.begin(TimerType::Work);
+        app.time_left = Duration::from_secs(20 * 60);
+        let type_time = |app: &mut PomodoroApp, text: &str| {
+            app.open_time_edit();
+            for c in text.chars() {
+                app.time_edit_key(Key::Char(c));
+            }
+            app.time_edit_key(Key::Enter);
+        };
+        
+        // 5 of 25 minutes done, then 40 minutes to go: 45 in all
+        type_time(&mut app, "40:00");
+        assert_eq!(app.time_left, Duration::from_secs(40 * 60));
+        assert_eq!(app.session_total, Duration::from_secs(45 * 60));
+        assert!((app.timer_progress() - 5.0 / 45.0).abs() < 1e-9);
+        
+        type_time(&mut app, "10:00");
+        assert_eq!(app.session_total, Duration::from_secs(15 * 60));
+    }
+
+    #[test]
+    fn completion_alerts_go_to_the_sink_unless_muted() {
+        let mut app = PomodoroApp::new(&Config { volume: 0.5, ..Config::default() });
+        let alerts = alert::RecordingAlerts::default();
+        app.alerts = Box::new(alerts.clone());
+        app.completion_alert();
+        app.muted = true;
+        app.completion_alert();
+        assert_eq!(
+            alerts.alerts(),
+            [Alert { track: None, volume: 0.5, vibrate: false, vibrate_command: None }]
+        );
+    }
+
+    #[test]
+    fn startup_data_is_passed_in() {
+        let data = AppData {
+            records: vec![HistoryEntry::at(Local::now()), HistoryEntry::at(Local::now())],
+            todos: Vec::new(),
+            planned_action: Some("review PR".to_string()),
+        };
+        let app = PomodoroApp::with_data(&Config { daily_goal: 2, ..Config::default() }, data);
+        assert_eq!(app.done_today, 2);
+        assert!(app.goal_announced);
+        assert_eq!(app.planned_action.as_deref(), Some("review PR"));
+        
+        let app = PomodoroApp::new(&Config { daily_goal: 2, ..Config::default() });
+        assert_eq!((app.done_today, app.planned_action), (0, None));
+    }
+
+    #[test]
+    fn resume_restores_the_snapshot() {
+        let mut app = PomodoroApp::new(&Config::default());
+        let session = SessionState {
+            timer_type: TimerType::ShortBreak,
+            current_session: 3,
+            total_sessions: 4,
+            remaining_secs: 200,
+            work_secs: 3000,
+            short_break_secs: 600,
+            long_break_secs: 1200,
+            paused: true,
+            long_break_interval: Some(2),
+            work_since_long_break: 1,
+            completed_work_sessions: 3,
+            long_breaks_taken: 1,
+        };
+        app.resume(&session, Duration::from_secs(200));
+        assert_eq!(app.state, AppState::Running);
+        assert_eq!((app.timer_type, app.current_session, app.total_sessions), (TimerType::ShortBreak, 3, 4));
+        assert_eq!((app.time_left, app.session_total), (Duration::from_secs(200), Duration::from_secs(600)));
+        assert_eq!(app.work_duration, Duration::from_secs(3000));
+        assert!(app.paused);
+        assert_eq!(app.session_state(), session);
+        
+        // The next break is the one the run was heading for, not the one a
+        // fresh start would give
+        assert_eq!(app.long_break_interval, 2);
+        assert_eq!((app.work_since_long_break, app.completed_work_sessions, app.long_breaks_taken), (1, 3, 1));
+        assert_eq!(app.break_type_after(2), TimerType::LongBreak);
+    }
+
+    #[test]
+    fn once_ends_after_one_session_without_a_break() {
+        let mut app = PomodoroApp::new(&Config { total_sessions: 1, ..Config::default() });
+        app.once = true;
+        app.start_timer();
+        app.time_left = Duration::ZERO;
+        let effects = app.advance_timer();
+        assert_eq!(
+            effects.into_iter().filter(|effect| !matches!(effect, Effect::Log(_))).collect::<Vec<_>>(),
+            [Effect::Notify("Focus session complete! 🎉".to_string()), Effect::PlaySound, Effect::Exit]
+        );
+        assert_ne!(app.state, AppState::BreakPrompt);
+    }
+
+    #[test]
+    fn end_time_is_blank_while_paused() {
+        let mut app = PomodoroApp::new(&Config::default());
+        app.start_timer();
+        let now = Local.with_ymd_and_hms(2025, 1, 14, 14, 27, 30).unwrap();
+        assert_eq!(app.ends_at(now), "ends at 14:52");
+        app.toggle_pause();
+        assert_eq!(app.ends_at(now), "ends at —");
+    }
+
+    #[test]
+    fn idle_work_pauses_until_the_next_key() {
+        let config = Config { auto_pause_idle_secs: 60, ..Config::default() };
+        let mut app = PomodoroApp::new(&config);
+        app.start_timer();
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        app.last_input = start;
+        
+        app.sync_clock(start);
+        app.sync_clock(at(59));
+        app.check_idle_at(at(59));
+        assert!(!app.paused);
+        app.sync_clock(at(60));
+        app.check_idle_at(at(60));
+        assert!(app.paused && app.idle_paused);
+        
+        assert!(app.note_key(at(90)));
+        assert!(!app.paused && !app.idle_paused);
+        assert_eq!(app.paused_total, Duration::from_secs(30));
+        // An ordinary key press isn't swallowed
+        assert!(!app.note_key(at(91)));
+        
+        // Breaks run on untouched
+        app.begin(TimerType::ShortBreak);
+        app.sync_clock(at(100));
+        app.sync_clock(at(200));
+        app.check_idle_at(at(200));
+        assert!(!app.paused);
+    }
+
+    #[test]
+    fn quitting_a_running_session_asks_first() {
+        let mut app = PomodoroApp::new(&Config::default());
+        assert!(app.request_quit());
+        
+        app.start_timer();
+        assert!(!app.request_quit());
+        assert!(app.quit_pending);
+        assert!(!app.quit_prompt_key(Key::Char('n')));
+        assert!(!app.quit_pending);
+        assert!(!app.request_quit());
+        assert!(app.quit_prompt_key(Key::Char('y')));
+        
+        app.confirm_quit = false;
+        assert!(app.request_quit());
+    }
+
+    #[test]
+    fn presets_fill_the_fields_and_edits_override_them() {
+        let mut config = Config::default();
+        assert_eq!(PomodoroApp::new(&config).preset_label(), "Classic");
+        
+        config.work_minutes = 40;
+        config.presets = vec![
+            Preset { name: "Deep Work".to_string(), work_minutes: 50, short_break_minutes: 10, long_break_minutes: 30, total_sessions: 3 },
+            Preset { name: "Classic".to_string(), work_minutes: 25, short_break_minutes: 5, long_break_minutes: 15, total_sessions: 4 },
+        ];
+        let mut app = PomodoroApp::new(&config);
+        assert_eq!(app.preset_label(), "Custom");
+        app.input(Action::PrevChoice);
+        assert_eq!(app.preset_label(), "Classic");
+        app.input(Action::NextChoice);
+        assert_eq!(app.preset_label(), "Deep Work");
+        
+        app.inputs[1].value = "15".to_string();
+        assert_eq!(app.preset_label(), "Deep Work (edited)");
+        app.start_timer();
+        assert_eq!(app.work_duration, Duration::from_secs(50 * 60));
+        assert_eq!(app.short_break_duration, Duration::from_secs(15 * 60));
+        assert_eq!(app.total_sessions, 3);
+    }
+
+    #[test]
+    fn daily_goal_is_announced_once_per_day() {
+        let mut app = PomodoroApp::new(&Config::default());
+        let notifier = notifier::RecordingNotifier::default();
+        app.notifier = Box::new(notifier.clone());
+        let day = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        assert_eq!(app.goal_progress(day), None);
+        
+        app.daily_goal = 2;
+        app.count_toward_goal(day);
+        assert_eq!(app.goal_progress(day).as_deref(), Some("1/2 today"));
+        app.count_toward_goal(day);
+        app.count_toward_goal(day);
+        assert_eq!(notifier.sent().len(), 1);
+        
+        // Local midnight starts a fresh count
+        let next = day.succ_opt().unwrap();
+        assert_eq!(app.goal_progress(next).as_deref(), Some("0/2 today"));
+        app.count_toward_goal(next);
+        app.count_toward_goal(next);
+        assert_eq!(notifier.sent().len(), 2);
+    }
+
+    #[test]
+    fn session_dots_fill_current_partially() {
+        assert_eq!(session_dots(2, 4, Some(0.5)), "█ ▌ ░ ░");
+        assert_eq!(session_dots(2, 4, Some(0.0)), "█ ░ ░ ░");
+        // On a break the session just worked counts as done
+        assert_eq!(session_dots(2, 4, None), "█ █ ░ ░");
+    }
+
+    #[test]
+    fn session_dots_mark_the_current_one_and_fall_back_to_numbers() {
+        let states = |progress| {
+            session_dot_cells(2, 3, progress).unwrap().into_iter().map(|(_, state)| state).collect::<Vec<_>>()
+        };
+        assert_eq!(states(Some(0.0)), [DotState::Done, DotState::Current, DotState::Upcoming]);
+        assert_eq!(states(None), [DotState::Done, DotState::Done, DotState::Upcoming]);
+        assert_eq!(session_dots(1, 1, Some(0.0)), "░");
+        assert_eq!(session_dots(20, 20, None), vec!["█"; 20].join(" "));
+        assert_eq!(session_dots(3, 21, Some(0.5)), "3/21");
+        assert_eq!(session_dot_cells(3, 21, None), None);
+    }
+
+    #[test]
+    fn blank_field_uses_default() {
+        let field = InputField::new("25", (1, 180));
+        assert_eq!(field.get_value(25), Ok(25));
+    }
+
+    #[test]
+    fn field_outside_its_limits_is_an_error() {
+        let field = |value: &str| InputField { value: value.to_string(), ..InputField::new("25", (1, 180)) };
+        assert_eq!(field("50").get_value(25), Ok(50));
+        assert_eq!(field(" 0 ").get_value(25), Err("must be 1–180".to_string()));
+        assert_eq!(field("181").validate(), Err("must be 1–180".to_string()));
+        assert_eq!(field("").validate(), Ok(25));
+    }
+
+    #[test]
+    fn garbage_field_is_an_error() {
+        let field = InputField { value: "5o".to_string(), ..InputField::new("25", (1, 180)) };
+        assert_eq!(field.get_value(25), Err("must be 1–180".to_string()));
+    }
+
+    #[test]
+    fn invalid_field_blocks_the_start() {
+        let mut app = PomodoroApp::new(&Config::default());
+        app.inputs[3].value = "0".to_string();
+        assert_eq!(app.field_error(3), Some("must be 1–20".to_string()));
+        assert_eq!(app.field_error(0), None);
+        app.start_timer();
+        assert_eq!(app.state, AppState::Setup);
+        assert_eq!(app.setup_error.as_deref(), Some("Total Sessions: must be 1–20"));
+        assert!(app.inputs[3].focused);
+    }
+
+    #[test]
+    fn setup_durations_take_minutes_or_mm_ss() {
+        let duration = |value: &str| {
+            InputField { value: value.to_string(), ..InputField::new("25", (0, 180)) }.get_duration(Duration::from_secs(1500))
+        };
+        assert_eq!(duration("25"), Ok(Duration::from_secs(1500)));
+        assert_eq!(duration("1:30"), Ok(Duration::from_secs(90)));
+        assert_eq!(duration("0:45"), Ok(Duration::from_secs(45)));
+        assert_eq!(duration(""), Ok(Duration::from_secs(1500)));
+        assert!(duration("0").is_err());
+        assert!(duration("1:75").is_err());
+        assert_eq!(duration("1:x").unwrap_err(), "must be 0–180");
+    }
+
+    #[test]
+    fn time_field_parses_and_validates() {
+        let mut field = InputField::new("MM:SS", (0, 60));
+        for c in "12:345".chars() {
+            field.push_time_char(c);
+        }
+        assert_eq!(field.value, "12:34");
+        assert_eq!(field.get_time(), Some(Duration::from_secs(12 * 60 + 34)));
+
+        let time = |value: &str| InputField { value: value.to_string(), ..InputField::new("", (0, 60)) }.get_time();
+        assert_eq!(time("5"), Some(Duration::from_secs(300)));
+        assert_eq!(time("5:"), Some(Duration::from_secs(300)));
+        assert_eq!(time("1:75"), None);
+        assert_eq!(time("61:00"), None);
+        assert_eq!(time("0:00"), None);
+        assert_eq!(time(":"), None);
+    }
+
+    #[test]
+    fn side_panels_need_room() {
+        let config = Config { max_content_width: 80, side_panels: true, ..Default::default() };
+        let mut app = PomodoroApp::new(&config);
+        app.width = 100;
+        assert_eq!(app.side_panel_width(), None);
+        app.width = 200;
+        assert_eq!(app.content_width(), 80);
+        assert_eq!(app.side_panel_width(), Some(60));
+
+        let (left, right) = app.side_panel_lines();
+        assert_eq!(left[2], "0/4 done");
+        assert_eq!(right[1], "Short break, 5 min");
+        app.current_session = 4;
+        assert_eq!(app.side_panel_lines().1[1], "All done");
+    }
+
+    #[test]
+    fn reload_applies_live_settings_only() {
+        let old = Config::default();
+        let mut app = PomodoroApp::new(&old);
+        let new = Config {
+            muted: true,
+            session_dots: true,
+            limits: config::Limits { work: (5, 60), ..Default::default() },
+            ..Default::default()
+        };
+
+        let (applied, deferred) = app.reload(&old, &new);
+        assert_eq!(applied, ["muted", "session_dots"]);
+        assert_eq!(deferred, ["limits"]);
+        assert!(app.muted && app.session_dots);
+        assert_eq!((app.inputs[0].min, app.inputs[0].max), (1, 180));
+
+        assert_eq!(app.reload(&new, &new), (vec![], vec![]));
+    }
+}
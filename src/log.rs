@@ -0,0 +1,27 @@
+use chrono::Local;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+// Diagnostics go to the --debug file, never to stderr, which would draw
+// over the TUI. Without --debug they are dropped
+static LOG: Mutex<Option<File>> = Mutex::new(None);
+
+// Appends to an existing log, so several runs can be compared
+pub fn init(path: &Path) -> io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    if let Ok(mut log) = LOG.lock() {
+        *log = Some(file);
+    }
+    Ok(())
+}
+
+pub fn debug(message: &str) {
+    let Ok(mut log) = LOG.lock() else {
+        return;
+    };
+    if let Some(file) = log.as_mut() {
+        let _ = writeln!(file, "{} {}", Local::now().format("%Y-%m-%d %H:%M:%S%.3f"), message);
+    }
+}
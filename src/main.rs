@@ -1,220 +1,249 @@
+use chrono::Local;
 use crossterm::{
     cursor, execute, queue,
-    style::{Color, Print, ResetColor, SetForegroundColor, SetAttribute, Attribute},
+    style::{Print, ResetColor, SetForegroundColor, SetAttribute, Attribute},
     terminal::{self, ClearType},
-    event::{self, Event, KeyCode, KeyEvent},
+    event::{self, Event, KeyEvent},
 };
 use std::io::{self, Write};
-use std::time::{Duration, Instant};
-
-// ASCII digits
-const ASCII_DIGITS: [[&str; 5]; 11] = [
-    ["██████", "█    █", "█    █", "█    █", "██████"], // 0
-    ["  ██  ", "  ██  ", "  ██  ", "  ██  ", "  ██  "], // 1
-    ["██████", "     █", "██████", "█     ", "██████"], // 2
-    ["██████", "     █", "██████", "     █", "██████"], // 3
-    ["█    █", "█    █", "██████", "     █", "     █"], // 4
-    ["██████", "█     ", "██████", "     █", "██████"], // 5
-    ["██████", "█     ", "██████", "█    █", "██████"], // 6
-    ["██████", "     █", "     █", "     █", "     █"], // 7
-    ["██████", "█    █", "██████", "█    █", "██████"], // 8
-    ["██████", "█    █", "██████", "     █", "██████"], // 9
-    ["      ", "  ██  ", "      ", "  ██  ", "      "], // :
+use std::time::{Duration, Instant, SystemTime};
+
+mod a11y;
+mod cli;
+mod lock;
+mod review;
+mod serve;
+mod signals;
+mod term;
+mod watch;
+
+use lock::LockStatus;
+use pomodoro_timer::config::{self, Config};
+use pomodoro_timer::error::{self, Error};
+use pomodoro_timer::keymap::Key;
+use pomodoro_timer::theme::{self, Color};
+use pomodoro_timer::{events, export, history, log, selftest, state, stats};
+use pomodoro_timer::{notifier, session_dot_cells, session_dots, truncate, volume_percent, Action, AppData, AppState, DotState, PomodoroApp, TimerType};
+use pomodoro_timer::{DOT_FRACTIONS, FIELD_LABELS};
+use std::process::ExitCode;
+
+// Glyph sets for the big clock, picked with `font`. Every glyph is six
+// columns wide; only the block font has letters, which the others borrow
+type Font = &'static [(char, [&'static str; 5])];
+
+const FONTS: &[(&str, Font)] = &[("block", BLOCK_FONT), ("thin", THIN_FONT), ("outline", OUTLINE_FONT)];
+
+const BLOCK_FONT: Font = &[
+    ('0', ["██████", "█    █", "█    █", "█    █", "██████"]),
+    ('1', ["  ██  ", "  ██  ", "  ██  ", "  ██  ", "  ██  "]),
+    ('2', ["██████", "     █", "██████", "█     ", "██████"]),
+    ('3', ["██████", "     █", "██████", "     █", "██████"]),
+    ('4', ["█    █", "█    █", "██████", "     █", "     █"]),
+    ('5', ["██████", "█     ", "██████", "     █", "██████"]),
+    ('6', ["██████", "█     ", "██████", "█    █", "██████"]),
+    ('7', ["██████", "     █", "     █", "     █", "     █"]),
+    ('8', ["██████", "█    █", "██████", "█    █", "██████"]),
+    ('9', ["██████", "█    █", "██████", "     █", "██████"]),
+    (':', ["      ", "  ██  ", "      ", "  ██  ", "      "]),
+    ('A', [" ████ ", "█    █", "██████", "█    █", "█    █"]),
+    ('B', ["█████ ", "█    █", "█████ ", "█    █", "█████ "]),
+    ('C', ["██████", "█     ", "█     ", "█     ", "██████"]),
+    ('D', ["█████ ", "█    █", "█    █", "█    █", "█████ "]),
+    ('E', ["██████", "█     ", "█████ ", "█     ", "██████"]),
+    ('F', ["██████", "█     ", "█████ ", "█     ", "█     "]),
+    ('G', ["██████", "█     ", "█  ███", "█    █", "██████"]),
+    ('H', ["█    █", "█    █", "██████", "█    █", "█    █"]),
+    ('I', ["██████", "  ██  ", "  ██  ", "  ██  ", "██████"]),
+    ('J', ["     █", "     █", "     █", "█    █", "██████"]),
+    ('K', ["█    █", "█   █ ", "████  ", "█   █ ", "█    █"]),
+    ('L', ["█     ", "█     ", "█     ", "█     ", "██████"]),
+    ('M', ["█    █", "██  ██", "█ ██ █", "█    █", "█    █"]),
+    ('N', ["█    █", "██   █", "█ █  █", "█  █ █", "█   ██"]),
+    ('O', ["██████", "█    █", "█    █", "█    █", "██████"]),
+    ('P', ["█████ ", "█    █", "█████ ", "█     ", "█     "]),
+    ('Q', ["██████", "█    █", "█    █", "█   ██", "██████"]),
+    ('R', ["█████ ", "█    █", "█████ ", "█   █ ", "█    █"]),
+    ('S', ["██████", "█     ", "██████", "     █", "██████"]),
+    ('T', ["██████", "  ██  ", "  ██  ", "  ██  ", "  ██  "]),
+    ('U', ["█    █", "█    █", "█    █", "█    █", "██████"]),
+    ('V', ["█    █", "█    █", "█    █", " █  █ ", "  ██  "]),
+    ('W', ["█    █", "█    █", "█ ██ █", "██  ██", "█    █"]),
+    ('X', ["█    █", " █  █ ", "  ██  ", " █  █ ", "█    █"]),
+    ('Y', ["█    █", " █  █ ", "  ██  ", "  ██  ", "  ██  "]),
+    ('Z', ["██████", "    █ ", "  ██  ", " █    ", "██████"]),
 ];
 
-#[derive(Clone, Copy, PartialEq, Debug)]
-enum AppState {
-    Setup,
-    Running,
-    BreakPrompt,
-}
+const THIN_FONT: Font = &[
+    ('0', ["┌────┐", "│    │", "│    │", "│    │", "└────┘"]),
+    ('1', ["  ─┐  ", "   │  ", "   │  ", "   │  ", "  ─┴─ "]),
+    ('2', ["─────┐", "     │", "┌────┘", "│     ", "└─────"]),
+    ('3', ["─────┐", "     │", " ────┤", "     │", "─────┘"]),
+    ('4', ["│    │", "│    │", "└────┤", "     │", "     │"]),
+    ('5', ["┌─────", "│     ", "└────┐", "     │", "─────┘"]),
+    ('6', ["┌─────", "│     ", "├────┐", "│    │", "└────┘"]),
+    ('7', ["─────┐", "     │", "     │", "     │", "     │"]),
+    ('8', ["┌────┐", "│    │", "├────┤", "│    │", "└────┘"]),
+    ('9', ["┌────┐", "│    │", "└────┤", "     │", "─────┘"]),
+    (':', ["      ", "  ••  ", "      ", "  ••  ", "      "]),
+];
 
-#[derive(Clone, Copy, PartialEq, Debug)]
-enum TimerType {
-    Work,
-    ShortBreak,
-    LongBreak,
-}
+// Drawn with double lines
+const OUTLINE_FONT: Font = &[
+    ('0', ["╔════╗", "║    ║", "║    ║", "║    ║", "╚════╝"]),
+    ('1', ["  ═╗  ", "   ║  ", "   ║  ", "   ║  ", "  ═╩═ "]),
+    ('2', ["═════╗", "     ║", "╔════╝", "║     ", "╚═════"]),
+    ('3', ["═════╗", "     ║", " ════╣", "     ║", "═════╝"]),
+    ('4', ["║    ║", "║    ║", "╚════╣", "     ║", "     ║"]),
+    ('5', ["╔═════", "║     ", "╚════╗", "     ║", "═════╝"]),
+    ('6', ["╔═════", "║     ", "╠════╗", "║    ║", "╚════╝"]),
+    ('7', ["═════╗", "     ║", "     ║", "     ║", "     ║"]),
+    ('8', ["╔════╗", "║    ║", "╠════╣", "║    ║", "╚════╝"]),
+    ('9', ["╔════╗", "║    ║", "╚════╣", "     ║", "═════╝"]),
+    (':', ["      ", "  ╔╗  ", "  ╚╝  ", "  ╔╗  ", "  ╚╝  "]),
+];
+
+// Characters missing from the font, spaces included, come out blank
+const BLANK_GLYPH: [&str; 5] = ["      "; 5];
 
-struct InputField {
-    value: String,
-    placeholder: String,
-    focused: bool,
+// Unknown names get the block font
+fn font(name: &str) -> Font {
+    FONTS.iter().find(|(n, _)| *n == name).map_or(BLOCK_FONT, |(_, font)| font)
 }
 
-impl InputField {
-    fn new(placeholder: &str) -> Self {
-        Self {
-            value: String::new(),
-            placeholder: placeholder.to_string(),
-            focused: false,
+fn render_big_text(text: &str, font: Font) -> Vec<String> {
+    let mut lines = vec![String::new(); 5];
+    for ch in text.chars().map(|ch| ch.to_ascii_uppercase()) {
+        let glyph = [font, BLOCK_FONT]
+            .iter()
+            .find_map(|font| font.iter().find(|(c, _)| *c == ch))
+            .map_or(BLANK_GLYPH, |(_, glyph)| *glyph);
+        for (line, row) in lines.iter_mut().zip(glyph) {
+            line.push_str(row);
+            line.push(' ');
         }
     }
+    lines
+}
 
-    fn get_value(&self, default: u32) -> u32 {
-        self.value.trim().parse().unwrap_or(default)
-    }
+// Blinking colon: shown on even seconds while the clock counts, and all
+// the time while it stands still, so a paused 24:59 never reads "24 59"
+fn colon_visible(app: &PomodoroApp, secs: u64) -> bool {
+    !app.blink_colon || !app.counting() || secs.is_multiple_of(2)
 }
 
-struct PomodoroApp {
-    state: AppState,
-    timer_type: TimerType,
-    paused: bool,
-    
-    // Input fields
-    inputs: Vec<InputField>,
-    focus_index: usize,
-    
-    // Timer durations
-    work_minutes: u32,
-    short_break_minutes: u32,
-    long_break_minutes: u32,
-    total_sessions: u32,
-    current_session: u32,
-    time_left: Duration,
-    
-    // Break prompt
-    next_break_type: Option<TimerType>,
-    
-    // Screen size
-    width: u16,
-    height: u16,
+// A hidden colon is drawn as blanks of the same width so the digits stay put
+fn render_big_time(seconds: u64, show_colon: bool, font: Font) -> Vec<String> {
+    let separator = if show_colon { ':' } else { ' ' };
+    render_big_text(&format!("{:02}{}{:02}", seconds / 60, separator, seconds % 60), font)
 }
 
-impl PomodoroApp {
-    fn new() -> Self {
-        let mut inputs = Vec::new();
-        inputs.push(InputField::new("25"));
-        inputs.push(InputField::new("5"));
-        inputs.push(InputField::new("15"));
-        inputs.push(InputField::new("4"));
-        inputs[0].focused = true;
-        
-        Self {
-            state: AppState::Setup,
-            timer_type: TimerType::Work,
-            paused: false,
-            inputs,
-            focus_index: 0,
-            work_minutes: 25,
-            short_break_minutes: 5,
-            long_break_minutes: 15,
-            total_sessions: 4,
-            current_session: 1,
-            time_left: Duration::from_secs(25 * 60),
-            next_break_type: None,
-            width: 0,
-            height: 0,
-        }
-    }
+// Below this the centered layouts run into each other
+const MIN_WIDTH: u16 = 50;
+const MIN_HEIGHT: u16 = 15;
 
-    fn start_timer(&mut self) {
-        self.work_minutes = self.inputs[0].get_value(25);
-        self.short_break_minutes = self.inputs[1].get_value(5);
-        self.long_break_minutes = self.inputs[2].get_value(15);
-        self.total_sessions = self.inputs[3].get_value(4);
-        
-        self.current_session = 1;
-        self.state = AppState::Running;
-        self.timer_type = TimerType::Work;
-        self.time_left = Duration::from_secs(self.work_minutes as u64 * 60);
-        self.paused = false;
-    }
-
-    fn advance_timer(&mut self) -> bool {
-        match self.timer_type {
-            TimerType::Work => {
-                // Work session finished - show break prompt
-                if self.current_session < self.total_sessions {
-                    if self.current_session % 4 == 0 {
-                        self.next_break_type = Some(TimerType::LongBreak);
-                        send_notification("Pomodoro", "Work session finished! Time for a long break.");
-                    } else {
-                        self.next_break_type = Some(TimerType::ShortBreak);
-                        send_notification("Pomodoro", "Work session finished! Time for a short break.");
-                    }
-                    self.state = AppState::BreakPrompt;
-                    play_sound();
-                    false  // Don't exit, show break prompt
-                } else {
-                    send_notification("Pomodoro", "All sessions completed! 🎉");
-                    true  // Exit - all sessions done
-                }
-            }
-            TimerType::ShortBreak | TimerType::LongBreak => {
-                // Break finished - go back to work
-                let msg = if self.timer_type == TimerType::ShortBreak {
-                    "Short break finished! Back to work."
-                } else {
-                    "Long break finished! Back to work."
-                };
-                send_notification("Pomodoro", msg);
-                
-                self.current_session += 1;
-                if self.current_session > self.total_sessions {
-                    send_notification("Pomodoro", "All sessions completed! 🎉");
-                    true  // Exit - all sessions done
-                } else {
-                    self.timer_type = TimerType::Work;
-                    self.time_left = Duration::from_secs(self.work_minutes as u64 * 60);
-                    self.paused = false;
-                    self.state = AppState::Running;
-                    play_sound();
-                    false  // Continue to next work session
-                }
-            }
-        }
-    }
+fn fits_terminal(width: u16, height: u16) -> bool {
+    width >= MIN_WIDTH && height >= MIN_HEIGHT
+}
 
-    fn start_break(&mut self) {
-        if let Some(break_type) = self.next_break_type {
-            self.timer_type = break_type;
-            let duration = match break_type {
-                TimerType::LongBreak => self.long_break_minutes,
-                TimerType::ShortBreak => self.short_break_minutes,
-                _ => 5,
-            };
-            self.time_left = Duration::from_secs(duration as u64 * 60);
-            self.paused = false;
-            self.state = AppState::Running;
-            self.next_break_type = None;
-            
-            eprintln!("✓ Break started: {:?}, duration: {} minutes", break_type, duration);
-        }
-    }
+// Drawn instead of any screen until the window is made big enough
+fn draw_too_small(app: &PomodoroApp) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    let message = format!("Terminal too small (need {}x{})", MIN_WIDTH, MIN_HEIGHT);
+    let message = truncate(&message, app.width as usize);
+    execute!(stdout, terminal::Clear(ClearType::All))?;
+    queue!(
+        stdout,
+        cursor::MoveTo((app.width / 2).saturating_sub((message.chars().count() / 2) as u16), app.height / 2),
+        SetForegroundColor(app.theme.alert.into()),
+        Print(message),
+        ResetColor
+    )?;
+    stdout.flush()
 }
 
-fn render_big_time(seconds: u64) -> Vec<String> {
-    let minutes = seconds / 60;
-    let secs = seconds % 60;
-    let time_str = format!("{:02}:{:02}", minutes, secs);
-    
-    let mut lines = vec![String::new(); 5];
-    
-    for ch in time_str.chars() {
-        let digit_idx = if ch == ':' { 10 } else { ch.to_digit(10).unwrap_or(0) as usize };
-        
-        for (i, line) in ASCII_DIGITS[digit_idx].iter().enumerate() {
-            lines[i].push_str(line);
-            lines[i].push(' ');
-        }
-    }
-    
-    lines
+// Rows the setup screen takes for `fields` label-and-box fields: the
+// title, planned action and preset lines, then the fields, the error and
+// the help line. Compact fields take one row each, boxed ones five
+fn setup_rows(fields: u16, compact: bool) -> u16 {
+    3 + fields * if compact { 1 } else { 5 } + 2
+}
+
+// One setup field, drawn boxed or on a single line
+struct SetupField {
+    label: String,
+    // Without the units, for the single-line layout
+    short_label: String,
+    text: String,
+    text_color: Color,
+    border_color: Color,
+    hint: Option<String>,
 }
 
 fn draw_setup(app: &PomodoroApp) -> io::Result<()> {
     let mut stdout = io::stdout();
+    let theme = &app.theme;
     execute!(stdout, terminal::Clear(ClearType::All))?;
     
-    let labels = [
-        "Work Duration (minutes):",
-        "Short Break (minutes):",
-        "Long Break (minutes):",
-        "Total Sessions:",
-    ];
+    // Boxed fields while they fit, one line each below that
+    let mut fields = Vec::new();
+    for (index, (input, (label, unit))) in app.inputs.iter().zip(FIELD_LABELS.iter()).enumerate() {
+        // In red with a hint while it can't be started with
+        let hint = app.field_error(index);
+        let border_color = if hint.is_some() {
+            theme.alert
+        } else if input.focused {
+            theme.title
+        } else {
+            theme.help
+        };
+        let (text, text_color) = if input.value.is_empty() {
+            (input.placeholder.clone(), theme.help)
+        } else {
+            (input.value.clone(), theme.text)
+        };
+        fields.push(SetupField {
+            label: format!("{} ({}{}–{}):", label, unit, input.min, input.max),
+            short_label: format!("{} ({}–{})", label, input.min, input.max),
+            text,
+            text_color,
+            border_color,
+            hint,
+        });
+    }
     
-    let start_row = (app.height / 2).saturating_sub(10);
+    let label = &app.label_input;
+    let (text, text_color) = if label.value.is_empty() {
+        (label.placeholder.clone(), theme.help)
+    } else if label.focused {
+        (format!("{}█", label.value), theme.text)
+    } else {
+        (label.value.clone(), theme.text)
+    };
+    fields.push(SetupField {
+        label: "Task Label:".to_string(),
+        short_label: "Task Label".to_string(),
+        text,
+        text_color,
+        border_color: if label.focused { theme.title } else { theme.help },
+        hint: None,
+    });
+    
+    if let Some(task) = app.todos.get(app.todo_index) {
+        let picker = format!("Focus Task ({}/{})", app.todo_index + 1, app.todos.len());
+        fields.push(SetupField {
+            label: format!("{}:", picker),
+            short_label: picker,
+            text: format!("◀ {} ▶", truncate(&task.text, 28)),
+            text_color: theme.text,
+            border_color: if app.task_picker_focused() { theme.title } else { theme.help },
+            hint: None,
+        });
+    }
+    
+    let compact = app.height < setup_rows(fields.len() as u16, false);
+    let start_row = app.height.saturating_sub(setup_rows(fields.len() as u16, compact)) / 2;
+    queue_goal_progress(&mut stdout, app)?;
+    queue_streak(&mut stdout, app)?;
     
     // Title
     let title = "POMODORO SETUP";
@@ -222,76 +251,61 @@ fn draw_setup(app: &PomodoroApp) -> io::Result<()> {
     queue!(
         stdout,
         cursor::MoveTo(title_col, start_row),
-        SetForegroundColor(Color::Cyan),
+        SetForegroundColor(theme.title.into()),
         SetAttribute(Attribute::Bold),
         Print(title),
         SetAttribute(Attribute::Reset)
     )?;
     
-    // Input fields
-    let mut current_row = start_row + 2;
-    for (input, label) in app.inputs.iter().zip(labels.iter()) {
-        // Label
-        let label_col = (app.width / 2).saturating_sub(20);
-        queue!(
-            stdout,
-            cursor::MoveTo(label_col, current_row),
-            SetForegroundColor(Color::DarkGrey),
-            Print(label)
-        )?;
-        current_row += 1;
-        
-        // Input box
-        let box_col = (app.width / 2).saturating_sub(20);
-        let border_color = if input.focused { Color::Cyan } else { Color::DarkGrey };
-        
-        // Top border
-        queue!(
-            stdout,
-            cursor::MoveTo(box_col, current_row),
-            SetForegroundColor(border_color),
-            Print("╭────────────────────────────────────────╮")
-        )?;
-        current_row += 1;
-        
-        // Input content
-        let display_text = if input.value.is_empty() {
-            &input.placeholder
-        } else {
-            &input.value
-        };
-        
-        let text_color = if input.value.is_empty() { Color::DarkGrey } else { Color::White };
+    if let Some(action) = &app.planned_action {
+        let text = truncate(&format!("Last time you planned: {}", action), app.width as usize);
         queue!(
             stdout,
-            cursor::MoveTo(box_col, current_row),
-            SetForegroundColor(border_color),
-            Print("│   "),
-            SetForegroundColor(text_color),
-            Print(format!("{:<32}", display_text)),
-            SetForegroundColor(border_color),
-            Print("   │")
+            cursor::MoveTo((app.width / 2).saturating_sub((text.chars().count() / 2) as u16), start_row + 1),
+            SetForegroundColor(theme.help.into()),
+            Print(text)
         )?;
-        current_row += 1;
-        
-        // Bottom border
+    }
+    
+    let preset = format!("Preset: ◀ {} ▶", app.preset_label());
+    queue!(
+        stdout,
+        cursor::MoveTo((app.width / 2).saturating_sub((preset.chars().count() / 2) as u16), start_row + 2),
+        SetForegroundColor(theme.text.into()),
+        Print(preset)
+    )?;
+    
+    // Input fields, task label and task picker
+    let mut current_row = start_row + 3;
+    for field in &fields {
+        current_row += queue_setup_field(&mut stdout, app, current_row, field, compact)?;
+    }
+    
+    if let Some(error) = &app.setup_error {
+        let error = truncate(error, app.width as usize);
         queue!(
             stdout,
-            cursor::MoveTo(box_col, current_row),
-            SetForegroundColor(border_color),
-            Print("╰────────────────────────────────────────╯")
+            cursor::MoveTo((app.width / 2).saturating_sub((error.chars().count() / 2) as u16), current_row),
+            SetForegroundColor(theme.alert.into()),
+            Print(error)
         )?;
-        current_row += 2;
     }
     
     // Help text
     current_row += 1;
-    let help = "[TAB] Switch  •  [ENTER] Start  •  [q] Quit";
-    let help_col = (app.width / 2).saturating_sub((help.len() / 2) as u16);
+    let help = app.help_text("setup", || {
+        format!(
+            "[TAB] Switch  •  [←/→] Preset  •  [ENTER] Start  •  [a] Auto Breaks: {}  •  [t] Stats  •  {}",
+            if app.auto_start_breaks { "on" } else { "off" },
+            app.keymap.quit_help()
+        )
+    });
+    let help = truncate(&help, app.width as usize);
+    let help_col = (app.width / 2).saturating_sub((help.chars().count() / 2) as u16);
     queue!(
         stdout,
         cursor::MoveTo(help_col, current_row),
-        SetForegroundColor(Color::DarkGrey),
+        SetForegroundColor(theme.help.into()),
         Print(help),
         ResetColor
     )?;
@@ -300,36 +314,222 @@ fn draw_setup(app: &PomodoroApp) -> io::Result<()> {
     Ok(())
 }
 
+// Rows used. Boxed: the label, the box and a row for the hint. Compact:
+// "Work Duration (1–180)  [25        ] must be 1–180" in MIN_WIDTH columns
+fn queue_setup_field(stdout: &mut io::Stdout, app: &PomodoroApp, row: u16, field: &SetupField, compact: bool) -> io::Result<u16> {
+    let theme = &app.theme;
+    if !compact {
+        let col = (app.width / 2).saturating_sub(20);
+        queue!(
+            stdout,
+            cursor::MoveTo(col, row),
+            SetForegroundColor(theme.help.into()),
+            Print(&field.label)
+        )?;
+        queue_input_box(stdout, col, row + 1, &field.text, field.text_color, field.border_color)?;
+        if let Some(hint) = &field.hint {
+            queue!(
+                stdout,
+                cursor::MoveTo(col + 2, row + 4),
+                SetForegroundColor(theme.alert.into()),
+                Print(hint)
+            )?;
+        }
+        return Ok(5);
+    }
+    
+    // Numbers get a short box, leaving room for the hint
+    let box_width = if field.hint.is_some() || field.text.chars().count() <= 10 { 10 } else { 23 };
+    let col = (app.width / 2).saturating_sub(MIN_WIDTH / 2);
+    queue!(
+        stdout,
+        cursor::MoveTo(col, row),
+        SetForegroundColor(theme.help.into()),
+        Print(format!("{:<23}", truncate(&field.short_label, 22))),
+        SetForegroundColor(field.border_color.into()),
+        Print("["),
+        SetForegroundColor(field.text_color.into()),
+        Print(format!("{:<width$}", truncate(&field.text, box_width), width = box_width)),
+        SetForegroundColor(field.border_color.into()),
+        Print("]")
+    )?;
+    if let Some(hint) = &field.hint {
+        let room = (MIN_WIDTH as usize).saturating_sub(23 + box_width + 3);
+        queue!(
+            stdout,
+            SetForegroundColor(theme.alert.into()),
+            Print(format!(" {}", truncate(hint, room)))
+        )?;
+    }
+    Ok(1)
+}
+
+fn queue_input_box(
+    stdout: &mut io::Stdout,
+    col: u16,
+    row: u16,
+    text: &str,
+    text_color: Color,
+    border_color: Color,
+) -> io::Result<()> {
+    queue!(
+        stdout,
+        cursor::MoveTo(col, row),
+        SetForegroundColor(border_color.into()),
+        Print("╭────────────────────────────────────────╮"),
+        cursor::MoveTo(col, row + 1),
+        Print("│   "),
+        SetForegroundColor(text_color.into()),
+        Print(format!("{:<32}", text)),
+        SetForegroundColor(border_color.into()),
+        Print("   │"),
+        cursor::MoveTo(col, row + 2),
+        Print("╰────────────────────────────────────────╯")
+    )
+}
+
+// Top-right corner, clear of the centered content
+fn queue_mute_indicator(stdout: &mut io::Stdout, app: &PomodoroApp) -> io::Result<()> {
+    if !app.muted {
+        return Ok(());
+    }
+    queue!(stdout, cursor::MoveTo(app.width.saturating_sub(3), 0), Print("🔇"))
+}
+
+// Top-left corner, opposite the mute indicator; nothing without a daily_goal
+fn queue_goal_progress(stdout: &mut io::Stdout, app: &PomodoroApp) -> io::Result<()> {
+    let Some(progress) = app.goal_progress(Local::now().date_naive()) else {
+        return Ok(());
+    };
+    queue!(
+        stdout,
+        cursor::MoveTo(1, 0),
+        SetForegroundColor(app.theme.help.into()),
+        Print(progress),
+        ResetColor
+    )
+}
+
+// "🔥 5 day streak" in the top-left corner, under any goal progress
+fn queue_streak(stdout: &mut io::Stdout, app: &PomodoroApp) -> io::Result<()> {
+    if app.stats.streak == 0 {
+        return Ok(());
+    }
+    let row = u16::from(app.daily_goal > 0);
+    queue!(
+        stdout,
+        cursor::MoveTo(1, row),
+        SetForegroundColor(app.theme.help.into()),
+        Print(format!("🔥 {} day streak", app.stats.streak)),
+        ResetColor
+    )
+}
+
+// Fills in `session_label_format`, e.g. "Pomodoro #{current}"
+fn session_label(format: &str, current: u32, total: u32) -> String {
+    format
+        .replace("{current}", &current.to_string())
+        .replace("{total}", &total.to_string())
+        .replace("{remaining}", &total.saturating_sub(current).to_string())
+        .replace("{index}", &current.saturating_sub(1).to_string())
+}
+
+// `width` cells between the brackets, filled in proportion to elapsed time
+// `smooth` ends the filled part in a partial cell, in eighths, so the bar
+// creeps along with every frame instead of stepping a cell at a time
+fn render_progress_bar(elapsed: Duration, total: Duration, width: u16, smooth: bool) -> String {
+    let fraction = if total.is_zero() {
+        0.0
+    } else {
+        (elapsed.as_secs_f64() / total.as_secs_f64()).clamp(0.0, 1.0)
+    };
+    if !smooth {
+        let filled = (fraction * width as f64).round() as usize;
+        return format!("[{}{}]", "█".repeat(filled), "░".repeat(width as usize - filled));
+    }
+    let eighths = (fraction * width as f64 * 8.0) as usize;
+    let filled = eighths / 8;
+    let partial = if filled < width as usize { DOT_FRACTIONS[eighths % 8].to_string() } else { String::new() };
+    let empty = (width as usize).saturating_sub(filled + 1);
+    format!("[{}{}{}]", "█".repeat(filled), partial, "░".repeat(empty))
+}
+
 fn draw_break_prompt(app: &PomodoroApp) -> io::Result<()> {
     let mut stdout = io::stdout();
+    let theme = &app.theme;
     execute!(stdout, terminal::Clear(ClearType::All))?;
     
     let (color, message) = match app.next_break_type {
-        Some(TimerType::LongBreak) => (Color::Green, "Time for a Long Break!"),
-        Some(TimerType::ShortBreak) => (Color::Yellow, "Time for a Short Break!"),
-        _ => (Color::White, "Break Time!"),
+        Some(TimerType::LongBreak) => (theme.long_break, "Time for a Long Break!"),
+        Some(TimerType::ShortBreak) => (theme.short_break, "Time for a Short Break!"),
+        _ => (theme.text, "Break Time!"),
     };
+    queue_mute_indicator(&mut stdout, app)?;
     
-    let start_row = (app.height / 2).saturating_sub(4);
+    // Custom art replaces the title, clipped to what fits above the prompt
+    let art: &[String] = match &app.celebration_art {
+        Some(lines) => &lines[..lines.len().min(app.height.saturating_sub(7) as usize)],
+        None => &[],
+    };
     
-    // Title
-    let title = "🎉 WORK SESSION COMPLETE! 🎉";
-    let title_col = (app.width / 2).saturating_sub((title.len() / 2) as u16);
-    queue!(
-        stdout,
-        cursor::MoveTo(title_col, start_row),
-        SetForegroundColor(Color::Cyan),
-        SetAttribute(Attribute::Bold),
-        Print(title),
-        SetAttribute(Attribute::Reset)
-    )?;
+    // A big "BREAK" under the title when there is no art and room for it
+    let big = render_big_text("BREAK", font(&app.font));
+    let big_fits = art.is_empty() && app.height >= 16 && app.width as usize > big[0].chars().count();
+    
+    let (start_row, body_row) = if big_fits {
+        let start_row = (app.height / 2).saturating_sub(7);
+        (start_row, start_row + 8)
+    } else if art.is_empty() {
+        let start_row = (app.height / 2).saturating_sub(4);
+        (start_row, start_row + 2)
+    } else {
+        let start_row = (app.height / 2).saturating_sub((art.len() as u16 + 7) / 2);
+        (start_row, start_row + art.len() as u16 + 1)
+    };
+    
+    if art.is_empty() {
+        // Title
+        let title = "🎉 WORK SESSION COMPLETE! 🎉";
+        let title_col = (app.width / 2).saturating_sub((title.len() / 2) as u16);
+        queue!(
+            stdout,
+            cursor::MoveTo(title_col, start_row),
+            SetForegroundColor(theme.title.into()),
+            SetAttribute(Attribute::Bold),
+            Print(title),
+            SetAttribute(Attribute::Reset)
+        )?;
+        if big_fits {
+            let col = (app.width / 2).saturating_sub((big[0].chars().count() / 2) as u16);
+            for (i, line) in big.iter().enumerate() {
+                queue!(
+                    stdout,
+                    cursor::MoveTo(col, start_row + 2 + i as u16),
+                    SetForegroundColor(color.into()),
+                    Print(line)
+                )?;
+            }
+        }
+    } else {
+        // ASCII art
+        for (i, line) in art.iter().enumerate() {
+            let line: String = line.chars().take(app.width as usize).collect();
+            let col = (app.width / 2).saturating_sub((line.chars().count() / 2) as u16);
+            queue!(
+                stdout,
+                cursor::MoveTo(col, start_row + i as u16),
+                SetForegroundColor(theme.title.into()),
+                Print(line)
+            )?;
+        }
+    }
     
     // Break message
     let msg_col = (app.width / 2).saturating_sub((message.len() / 2) as u16);
     queue!(
         stdout,
-        cursor::MoveTo(msg_col, start_row + 2),
-        SetForegroundColor(color),
+        cursor::MoveTo(msg_col, body_row),
+        SetForegroundColor(color.into()),
         SetAttribute(Attribute::Bold),
         Print(message),
         SetAttribute(Attribute::Reset)
@@ -340,18 +540,192 @@ fn draw_break_prompt(app: &PomodoroApp) -> io::Result<()> {
     let prompt_col = (app.width / 2).saturating_sub((prompt.len() / 2) as u16);
     queue!(
         stdout,
-        cursor::MoveTo(prompt_col, start_row + 4),
-        SetForegroundColor(Color::White),
+        cursor::MoveTo(prompt_col, body_row + 2),
+        SetForegroundColor(theme.text.into()),
         Print(prompt)
     )?;
     
     // Help text
-    let help = "[ENTER] Start Break  •  [s] Skip  •  [q] Quit";
-    let help_col = (app.width / 2).saturating_sub((help.len() / 2) as u16);
+    let help = app.help_text("break_prompt", || app.keymap.prompt_help());
+    let help_col = (app.width / 2).saturating_sub((help.chars().count() / 2) as u16);
+    queue!(
+        stdout,
+        cursor::MoveTo(help_col, body_row + 4),
+        SetForegroundColor(theme.help.into()),
+        Print(help),
+        ResetColor
+    )?;
+    
+    if let Some(selected) = app.skip_reason_choice {
+        queue_skip_reasons(&mut stdout, app, selected)?;
+    }
+    
+    stdout.flush()?;
+    Ok(())
+}
+
+// Small box over the middle of a running timer; the countdown keeps going
+// behind it
+fn queue_quit_prompt(stdout: &mut io::Stdout, app: &PomodoroApp) -> io::Result<()> {
+    let theme = &app.theme;
+    let text = "Quit? [y/N]";
+    let border = "─".repeat(text.len() + 2);
+    let left = (app.width / 2).saturating_sub(text.len() as u16 / 2 + 2);
+    let top = (app.height / 2).saturating_sub(1);
+    queue!(
+        stdout,
+        SetForegroundColor(theme.help.into()),
+        cursor::MoveTo(left, top),
+        Print(format!("┌{}┐", border)),
+        cursor::MoveTo(left, top + 1),
+        Print("│ "),
+        SetForegroundColor(theme.alert.into()),
+        SetAttribute(Attribute::Bold),
+        Print(text),
+        SetAttribute(Attribute::Reset),
+        SetForegroundColor(theme.help.into()),
+        Print(" │"),
+        cursor::MoveTo(left, top + 2),
+        Print(format!("└{}┘", border)),
+        ResetColor
+    )
+}
+
+// Boxed list over the middle of the break screens
+fn queue_skip_reasons(stdout: &mut io::Stdout, app: &PomodoroApp, selected: usize) -> io::Result<()> {
+    let theme = &app.theme;
+    let title = "Why skip this break?";
+    let hint = "[↑↓/1-9] Pick  •  [ENTER] Confirm  •  [ESC] No reason";
+    let items: Vec<String> = app
+        .skip_reasons
+        .iter()
+        .enumerate()
+        .map(|(i, reason)| format!("{} {}. {}", if i == selected { '▶' } else { ' ' }, i + 1, reason))
+        .collect();
+    let inner = items
+        .iter()
+        .map(|item| item.chars().count())
+        .chain([title.len(), hint.chars().count()])
+        .max()
+        .unwrap_or(0)
+        .min(app.width.saturating_sub(4) as usize);
+    
+    let mut rows = vec![(title.to_string(), theme.title), (String::new(), theme.text)];
+    for (i, item) in items.into_iter().enumerate() {
+        rows.push((item, if i == selected { theme.text } else { theme.help }));
+    }
+    rows.push((String::new(), theme.text));
+    rows.push((hint.to_string(), theme.help));
+    
+    let left = (app.width / 2).saturating_sub(inner as u16 / 2 + 2);
+    let top = (app.height / 2).saturating_sub(rows.len() as u16 / 2 + 1);
+    let border = "─".repeat(inner + 2);
+    queue!(stdout, cursor::MoveTo(left, top), SetForegroundColor(theme.help.into()), Print(format!("┌{}┐", border)))?;
+    for (i, (text, color)) in rows.iter().enumerate() {
+        queue!(
+            stdout,
+            cursor::MoveTo(left, top + 1 + i as u16),
+            SetForegroundColor(theme.help.into()),
+            Print("│ "),
+            SetForegroundColor((*color).into()),
+            Print(format!("{:<width$}", truncate(text, inner), width = inner)),
+            SetForegroundColor(theme.help.into()),
+            Print(" │")
+        )?;
+    }
+    queue!(
+        stdout,
+        cursor::MoveTo(left, top + 1 + rows.len() as u16),
+        Print(format!("└{}┘", border)),
+        ResetColor
+    )?;
+    Ok(())
+}
+
+fn draw_ritual(app: &PomodoroApp) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    let theme = &app.theme;
+    execute!(stdout, terminal::Clear(ClearType::All))?;
+    
+    let row = (app.height / 2).saturating_sub(2);
+    let title = format!("FOCUS RITUAL {}/{}", app.ritual_index + 1, app.ritual.len());
+    queue!(
+        stdout,
+        cursor::MoveTo((app.width / 2).saturating_sub((title.len() / 2) as u16), row),
+        SetForegroundColor(theme.title.into()),
+        SetAttribute(Attribute::Bold),
+        Print(&title),
+        SetAttribute(Attribute::Reset)
+    )?;
+    
+    let prompt = truncate(app.ritual.get(app.ritual_index).map_or("", String::as_str), app.width as usize);
+    queue!(
+        stdout,
+        cursor::MoveTo((app.width / 2).saturating_sub((prompt.chars().count() / 2) as u16), row + 2),
+        SetForegroundColor(theme.text.into()),
+        Print(&prompt)
+    )?;
+    
+    let help = app.help_text("ritual", || format!("[ENTER] Done  •  [s] Skip Ritual  •  {}", app.keymap.quit_help()));
+    queue!(
+        stdout,
+        cursor::MoveTo((app.width / 2).saturating_sub((help.chars().count() / 2) as u16), row + 4),
+        SetForegroundColor(theme.help.into()),
+        Print(help),
+        ResetColor
+    )?;
+    
+    stdout.flush()?;
+    Ok(())
+}
+
+fn draw_stats(app: &PomodoroApp) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    let theme = &app.theme;
+    execute!(stdout, terminal::Clear(ClearType::All))?;
+    
+    let summary = &app.stats;
+    let lines = if summary.all_time == 0 {
+        vec!["No sessions yet".to_string()]
+    } else {
+        vec![
+            format!("Today:        {:>5}", summary.today),
+            format!("This week:    {:>5}", summary.this_week),
+            format!("All time:     {:>5}", summary.all_time),
+            String::new(),
+            format!("Focus time: {:>7}", stats::format_duration(summary.focus_secs)),
+            format!("Streak:       {:>5} day{}", summary.streak, if summary.streak == 1 { "" } else { "s" }),
+        ]
+    };
+    
+    let row = (app.height / 2).saturating_sub((lines.len() as u16 + 4) / 2);
+    let title = "COMPLETED POMODOROS";
+    queue!(
+        stdout,
+        cursor::MoveTo((app.width / 2).saturating_sub((title.len() / 2) as u16), row),
+        SetForegroundColor(theme.title.into()),
+        SetAttribute(Attribute::Bold),
+        Print(title),
+        SetAttribute(Attribute::Reset)
+    )?;
+    
+    // Left-aligned as a block so the numbers line up
+    let block_width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+    let col = (app.width / 2).saturating_sub((block_width / 2) as u16);
+    for (i, line) in lines.iter().enumerate() {
+        queue!(
+            stdout,
+            cursor::MoveTo(col, row + 2 + i as u16),
+            SetForegroundColor(theme.text.into()),
+            Print(line)
+        )?;
+    }
+    
+    let help = app.help_text("stats", || format!("[ESC] Back  •  {}", app.keymap.quit_help()));
     queue!(
         stdout,
-        cursor::MoveTo(help_col, start_row + 6),
-        SetForegroundColor(Color::DarkGrey),
+        cursor::MoveTo((app.width / 2).saturating_sub((help.chars().count() / 2) as u16), row + 3 + lines.len() as u16),
+        SetForegroundColor(theme.help.into()),
         Print(help),
         ResetColor
     )?;
@@ -360,34 +734,212 @@ fn draw_break_prompt(app: &PomodoroApp) -> io::Result<()> {
     Ok(())
 }
 
+fn draw_work_prompt(app: &PomodoroApp) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    let theme = &app.theme;
+    execute!(stdout, terminal::Clear(ClearType::All))?;
+    
+    let row = app.height / 2;
+    let message = "Break over — ready to focus?";
+    queue!(
+        stdout,
+        cursor::MoveTo((app.width / 2).saturating_sub((message.chars().count() / 2) as u16), row.saturating_sub(2)),
+        SetForegroundColor(theme.work.into()),
+        SetAttribute(Attribute::Bold),
+        Print(message),
+        SetAttribute(Attribute::Reset)
+    )?;
+    
+    let next = format!("Up next: work session {}/{}", app.current_session, app.total_sessions);
+    queue!(
+        stdout,
+        cursor::MoveTo((app.width / 2).saturating_sub((next.len() / 2) as u16), row),
+        SetForegroundColor(theme.text.into()),
+        Print(&next)
+    )?;
+    
+    let help = app.help_text("work_prompt", || format!("[ENTER] Start  •  {}", app.keymap.quit_help()));
+    queue!(
+        stdout,
+        cursor::MoveTo((app.width / 2).saturating_sub((help.chars().count() / 2) as u16), row + 2),
+        SetForegroundColor(theme.help.into()),
+        Print(help),
+        ResetColor
+    )?;
+    
+    stdout.flush()?;
+    Ok(())
+}
+
+// Counts up from the bell in red, "+MM:SS"
+fn draw_overtime(app: &PomodoroApp) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    let theme = &app.theme;
+    execute!(stdout, terminal::Clear(ClearType::All))?;
+    queue_mute_indicator(&mut stdout, app)?;
+    
+    let start_row = (app.height / 2).saturating_sub(5);
+    let title = format!("{} — OVERTIME", session_label(&app.session_label_format, app.current_session, app.total_sessions));
+    queue!(
+        stdout,
+        cursor::MoveTo((app.width / 2).saturating_sub((title.chars().count() / 2) as u16), start_row),
+        SetForegroundColor(theme.alert.into()),
+        SetAttribute(Attribute::Bold),
+        Print(&title),
+        SetAttribute(Attribute::Reset)
+    )?;
+    
+    let lines = render_big_time(app.overtime_at(Instant::now()).as_secs(), true, font(&app.font));
+    let width = lines[0].chars().count() + 2;
+    let col = (app.width / 2).saturating_sub((width / 2) as u16);
+    for (i, line) in lines.iter().enumerate() {
+        let prefix = if i == 2 { "+ " } else { "  " };
+        queue!(
+            stdout,
+            cursor::MoveTo(col, start_row + 2 + i as u16),
+            SetForegroundColor(theme.alert.into()),
+            Print(prefix),
+            Print(line)
+        )?;
+    }
+    
+    let help = app.help_text("overtime", || app.keymap.overtime_help());
+    queue!(
+        stdout,
+        cursor::MoveTo((app.width / 2).saturating_sub((help.chars().count() / 2) as u16), start_row + 8),
+        SetForegroundColor(theme.help.into()),
+        Print(help),
+        ResetColor
+    )?;
+    
+    if app.quit_pending {
+        queue_quit_prompt(&mut stdout, app)?;
+    }
+    
+    stdout.flush()?;
+    Ok(())
+}
+
+fn draw_break_leadin(app: &PomodoroApp) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    let theme = &app.theme;
+    execute!(stdout, terminal::Clear(ClearType::All))?;
+    
+    let color = match app.next_break_type {
+        Some(TimerType::LongBreak) => theme.long_break,
+        _ => theme.short_break,
+    };
+    queue_mute_indicator(&mut stdout, app)?;
+    let secs_left = app.leadin_until.map_or(0, |until| {
+        until.saturating_duration_since(Instant::now()).as_secs_f64().ceil() as u64
+    });
+    let message = format!("Break starting in {}...", secs_left);
+    let row = app.height / 2;
+    queue!(
+        stdout,
+        cursor::MoveTo((app.width / 2).saturating_sub((message.len() / 2) as u16), row.saturating_sub(1)),
+        SetForegroundColor(color.into()),
+        SetAttribute(Attribute::Bold),
+        Print(&message),
+        SetAttribute(Attribute::Reset)
+    )?;
+    
+    let help = app.help_text("break_leadin", || format!("[s] Skip Break  •  {}", app.keymap.quit_help()));
+    queue!(
+        stdout,
+        cursor::MoveTo((app.width / 2).saturating_sub((help.chars().count() / 2) as u16), row + 1),
+        SetForegroundColor(theme.help.into()),
+        Print(help),
+        ResetColor
+    )?;
+    
+    if let Some(selected) = app.skip_reason_choice {
+        queue_skip_reasons(&mut stdout, app, selected)?;
+    }
+    
+    stdout.flush()?;
+    Ok(())
+}
+
 fn draw_timer(app: &PomodoroApp) -> io::Result<()> {
+    if app.clock_hidden() {
+        return draw_focus(app);
+    }
+    
     let mut stdout = io::stdout();
+    let theme = &app.theme;
     execute!(stdout, terminal::Clear(ClearType::All))?;
     
     let (color, mode_str) = match app.timer_type {
         TimerType::Work => (
-            Color::Cyan,
-            format!("WORK SESSION {}/{}", app.current_session, app.total_sessions)
+            theme.work,
+            session_label(&app.session_label_format, app.current_session, app.total_sessions)
         ),
-        TimerType::ShortBreak => (Color::Yellow, "SHORT BREAK".to_string()),
-        TimerType::LongBreak => (Color::Green, "LONG BREAK".to_string()),
+        TimerType::ShortBreak => (theme.short_break, "SHORT BREAK".to_string()),
+        TimerType::LongBreak => (theme.long_break, "LONG BREAK".to_string()),
     };
     
     let start_row = (app.height / 2).saturating_sub(6);
     
-    // Title
-    let title_col = (app.width / 2).saturating_sub((mode_str.len() / 2) as u16);
+    // Title, with the session dots on the row under it
+    let title_col = (app.width / 2).saturating_sub((mode_str.chars().count() / 2) as u16);
     queue!(
         stdout,
-        cursor::MoveTo(title_col, start_row),
-        SetForegroundColor(color),
+        cursor::MoveTo(title_col, start_row.saturating_sub(1)),
+        SetForegroundColor(color.into()),
         SetAttribute(Attribute::Bold),
         Print(&mode_str),
         SetAttribute(Attribute::Reset)
     )?;
+    queue_mute_indicator(&mut stdout, app)?;
+    queue_goal_progress(&mut stdout, app)?;
+    
+    // Session dots; with `session_dots` on the current one fills up as the
+    // timer runs. During a break the session before it is done
+    let progress = match app.timer_type {
+        TimerType::Work if app.session_dots => Some(app.timer_progress()),
+        TimerType::Work => Some(0.0),
+        _ => None,
+    };
+    let dots = session_dots(app.current_session, app.total_sessions, progress);
+    queue!(stdout, cursor::MoveTo((app.width / 2).saturating_sub((dots.chars().count() / 2) as u16), start_row))?;
+    match session_dot_cells(app.current_session, app.total_sessions, progress) {
+        Some(cells) => {
+            for (i, (dot, state)) in cells.into_iter().enumerate() {
+                let dot_color = match state {
+                    DotState::Done => theme.text,
+                    DotState::Current => color,
+                    DotState::Upcoming => theme.help,
+                };
+                let gap = if i == 0 { "" } else { " " };
+                queue!(stdout, Print(gap), SetForegroundColor(dot_color.into()), Print(dot))?;
+            }
+        }
+        None => queue!(stdout, SetForegroundColor(theme.help.into()), Print(&dots))?,
+    }
+    
+    // Task label and focus task
+    let mut parts = Vec::new();
+    if !app.task_label.is_empty() {
+        parts.push(app.task_label.clone());
+    }
+    if let Some(task) = &app.current_task {
+        parts.push(format!("▸ {}", task.text));
+    }
+    if !parts.is_empty() {
+        let text = truncate(&parts.join("  "), app.content_width().saturating_sub(2) as usize);
+        let task_col = (app.width / 2).saturating_sub((text.chars().count() / 2) as u16);
+        queue!(
+            stdout,
+            cursor::MoveTo(task_col, start_row + 1),
+            SetForegroundColor(theme.text.into()),
+            Print(text)
+        )?;
+    }
     
     // Big timer
-    let lines = render_big_time(app.time_left.as_secs());
+    let secs = app.secs_left();
+    let lines = render_big_time(secs, colon_visible(app, secs), font(&app.font));
     
     for (i, line) in lines.iter().enumerate() {
         let line_width = line.chars().count();
@@ -395,240 +947,728 @@ fn draw_timer(app: &PomodoroApp) -> io::Result<()> {
         queue!(
             stdout,
             cursor::MoveTo(col, start_row + 2 + i as u16),
-            SetForegroundColor(color),
+            SetForegroundColor(color.into()),
             Print(line)
         )?;
     }
     
+    let ends = app.ends_at(Local::now());
+    queue!(
+        stdout,
+        cursor::MoveTo((app.width / 2).saturating_sub((ends.chars().count() / 2) as u16), start_row + 7),
+        SetForegroundColor(theme.help.into()),
+        Print(ends)
+    )?;
+    
     // Status
-    let status = if app.paused { "PAUSED" } else { "RUNNING" };
-    let status_col = (app.width / 2).saturating_sub((status.len() / 2) as u16);
+    let mut status = match app.deferred_until {
+        Some(until) => {
+            let left = until.saturating_duration_since(Instant::now()).as_secs();
+            format!("DEFERRED (meeting) {:02}:{:02} left", left / 60, left % 60)
+        }
+        None if app.idle_paused => "AUTO-PAUSED (idle)".to_string(),
+        None if app.paused || app.timer_held() => "PAUSED".to_string(),
+        None => "RUNNING".to_string(),
+    };
+    if app.interruptions > 0 {
+        status.push_str(&format!("  •  {} interruption{}", app.interruptions, if app.interruptions == 1 { "" } else { "s" }));
+    }
+    // This session's pauses so far, the current one included
+    let paused = app.total_paused().as_secs();
+    if paused > 0 {
+        let amount = if paused < 60 { format!("{}s", paused) } else { format!("{}m", paused / 60) };
+        status.push_str(&format!("  •  paused {} total", amount));
+    }
+    if app.show_run_progress {
+        status.push_str(&format!("  •  Run {:.0}% complete", app.run_progress() * 100.0));
+    }
+    let status_col = (app.width / 2).saturating_sub((status.chars().count() / 2) as u16);
     queue!(
         stdout,
         cursor::MoveTo(status_col, start_row + 8),
-        SetForegroundColor(Color::DarkGrey),
+        SetForegroundColor(theme.help.into()),
         Print(status)
     )?;
     
+    // Progress bar
+    let bar_width = app.content_width().saturating_sub(10).min(40);
+    let bar = render_progress_bar(app.session_total.saturating_sub(app.time_left), app.session_total, bar_width, app.smooth_progress);
+    queue!(
+        stdout,
+        cursor::MoveTo((app.width / 2).saturating_sub((bar.chars().count() / 2) as u16), start_row + 9),
+        SetForegroundColor(color.into()),
+        Print(bar)
+    )?;
+    
     // Help
-    let help = "[SPACE] Pause  •  [s] Skip  •  [↑/↓] +/- 1m  •  [q] Quit";
-    let help_col = (app.width / 2).saturating_sub((help.len() / 2) as u16);
+    let help = app.help_text("running", || app.keymap.running_help(app.current_task.is_some()));
+    let help_col = (app.width / 2).saturating_sub((help.chars().count() / 2) as u16);
     queue!(
         stdout,
-        cursor::MoveTo(help_col, start_row + 10),
-        SetForegroundColor(Color::DarkGrey),
+        cursor::MoveTo(help_col, start_row + 11),
+        SetForegroundColor(theme.help.into()),
         Print(help),
         ResetColor
     )?;
     
+    // New remaining time
+    if let Some(field) = &app.time_edit {
+        let col = (app.width / 2).saturating_sub(21);
+        queue!(
+            stdout,
+            cursor::MoveTo(col, start_row + 13),
+            SetForegroundColor(theme.help.into()),
+            Print("Remaining time (MM:SS), ENTER to set, ESC to cancel:")
+        )?;
+        let text = if field.value.is_empty() { field.placeholder.clone() } else { format!("{}█", field.value) };
+        queue_input_box(&mut stdout, col, start_row + 14, &text, theme.text, theme.title)?;
+    }
+    
+    // New task label
+    if let Some(label) = &app.label_edit {
+        let col = (app.width / 2).saturating_sub(21);
+        queue!(
+            stdout,
+            cursor::MoveTo(col, start_row + 13),
+            SetForegroundColor(theme.help.into()),
+            Print("Task label, ENTER to set, ESC to cancel:")
+        )?;
+        queue_input_box(&mut stdout, col, start_row + 14, &format!("{}█", label), theme.text, theme.title)?;
+    }
+    
+    // Side panels flanking the clock on wide terminals
+    if let Some(panel_width) = app.side_panel_width() {
+        let (left, right) = app.side_panel_lines();
+        let right_start = app.width - panel_width;
+        for (start, lines) in [(0, left), (right_start, right)] {
+            for (i, line) in lines.iter().enumerate() {
+                let line = truncate(line, panel_width.saturating_sub(2) as usize);
+                let col = start + (panel_width / 2).saturating_sub((line.chars().count() / 2) as u16);
+                // Headings and the dots in the session color, values in plain text
+                let line_color = if line.chars().any(char::is_lowercase) { theme.text } else { color };
+                queue!(
+                    stdout,
+                    cursor::MoveTo(col, start_row + 2 + i as u16),
+                    SetForegroundColor(line_color.into()),
+                    Print(line)
+                )?;
+            }
+        }
+    }
+    
+    // Command line
+    let bottom_row = app.height.saturating_sub(1);
+    if let Some(line) = &app.command_line {
+        queue!(
+            stdout,
+            cursor::MoveTo(0, bottom_row),
+            SetForegroundColor(theme.text.into()),
+            Print(format!(":{}█", line)),
+            ResetColor
+        )?;
+    } else if let Some(message) = &app.command_message {
+        queue!(
+            stdout,
+            cursor::MoveTo(0, bottom_row),
+            SetForegroundColor(theme.help.into()),
+            Print(message),
+            ResetColor
+        )?;
+    }
+    
+    if app.quit_pending {
+        queue_quit_prompt(&mut stdout, app)?;
+    }
+    
     stdout.flush()?;
     Ok(())
 }
 
-fn send_notification(title: &str, message: &str) {
-    #[cfg(not(target_os = "windows"))]
-    {
-        let _ = notify_rust::Notification::new()
-            .summary(title)
-            .body(message)
-            .show();
-    }
-    
-    #[cfg(target_os = "windows")]
-    {
-        use std::process::Command;
-        let _ = Command::new("powershell")
-            .args(&["-Command", &format!("
-                [Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] | Out-Null;
-                $Template = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02);
-                $RawXml = [xml] $Template.GetXml();
-                ($RawXml.toast.visual.binding.text|where {{$_.id -eq '1'}}).AppendChild($RawXml.CreateTextNode('{}')) | Out-Null;
-                ($RawXml.toast.visual.binding.text|where {{$_.id -eq '2'}}).AppendChild($RawXml.CreateTextNode('{}')) | Out-Null;
-                $SerializedXml = New-Object Windows.Data.Xml.Dom.XmlDocument;
-                $SerializedXml.LoadXml($RawXml.OuterXml);
-                $Toast = [Windows.UI.Notifications.ToastNotification]::new($SerializedXml);
-                [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('Pomodoro').Show($Toast);
-            ", title, message)])
-            .output();
-    }
-}
-
-fn play_sound() {
-    use std::process::Command;
-    
-    let song_path = "/home/rohitrai/Music/music.mp3";
-    
-    eprintln!("🔊 Playing sound: {}", song_path);
-    
-    #[cfg(target_os = "windows")]
-    {
-        let _ = Command::new("powershell")
-            .args(&["-c", &format!("(New-Object Media.SoundPlayer '{}').PlaySync()", song_path)])
-            .spawn();
-    }
-    
-    #[cfg(not(target_os = "windows"))]
-    {
-        match Command::new("mpv")
-            .arg("--no-video")
-            .arg(song_path)
-            .spawn() {
-                Ok(_) => eprintln!("✓ mpv started"),
-                Err(e) => eprintln!("✗ mpv failed: {}", e),
-            }
+// Strict focus: only the word FOCUS, the countdown stays out of sight
+fn draw_focus(app: &PomodoroApp) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    let theme = &app.theme;
+    execute!(stdout, terminal::Clear(ClearType::All))?;
+    
+    let row = app.height / 2;
+    let title = "FOCUS";
+    queue!(
+        stdout,
+        cursor::MoveTo((app.width / 2).saturating_sub((title.len() / 2) as u16), row),
+        SetForegroundColor(theme.work.into()),
+        SetAttribute(Attribute::Bold),
+        Print(title),
+        SetAttribute(Attribute::Reset)
+    )?;
+    
+    if app.paused {
+        let status = if app.idle_paused { "AUTO-PAUSED (idle)" } else { "PAUSED" };
+        queue!(
+            stdout,
+            cursor::MoveTo((app.width / 2).saturating_sub((status.len() / 2) as u16), row + 2),
+            SetForegroundColor(theme.help.into()),
+            Print(status)
+        )?;
     }
-}
-
-fn stop_sound() {
-    use std::process::Command;
     
-    eprintln!("🔇 Stopping sound");
+    let help = app.help_text("focus", || "[any key] Show time".to_string());
+    queue!(
+        stdout,
+        cursor::MoveTo((app.width / 2).saturating_sub((help.chars().count() / 2) as u16), app.height.saturating_sub(2)),
+        SetForegroundColor(theme.help.into()),
+        Print(help),
+        ResetColor
+    )?;
     
-    #[cfg(target_os = "windows")]
-    {
-        let _ = Command::new("taskkill")
-            .args(&["/F", "/IM", "mpv.exe"])
-            .output();
+    if app.quit_pending {
+        queue_quit_prompt(&mut stdout, app)?;
     }
     
-    #[cfg(not(target_os = "windows"))]
-    {
-        let _ = Command::new("pkill")
-            .arg("mpv")
-            .output();
+    stdout.flush()?;
+    Ok(())
+}
+
+// Through the same player as the alerts, so it follows the volume and
+// `stop`, and is reaped once done
+fn play_ui_sound(app: &PomodoroApp) {
+    if app.muted {
+        return;
+    }
+    if let Some(path) = app.ui_sound.as_deref() {
+        app.audio.play(path.into(), app.volume);
     }
 }
 
-fn run_app() -> io::Result<()> {
-    let mut app = PomodoroApp::new();
-    let mut stdout = io::stdout();
+fn run_app(cli: &cli::Cli) -> error::Result<()> {
+    let mut config = config::load_config();
+    cli.check_limits(&config.limits).map_err(Error::Usage)?;
+    override_durations(&mut config, cli);
+    let mut app = PomodoroApp::with_data(&config, AppData::load(&config));
+    app.no_tick = cli.no_tick;
+    app.once = cli.once;
+    if cli.resume {
+        let snapshot = state::load_session().and_then(|(session, written)| {
+            state::time_to_resume(&session, written, SystemTime::now()).map(|left| (session, left))
+        });
+        match snapshot {
+            Some((session, left)) => app.resume(&session, Duration::from_secs(left)),
+            None => app.setup_error = Some("Nothing to resume: no unfinished session was saved".to_string()),
+        }
+    } else if cli.durations().is_some() || cli.once {
+        app.start_timer();
+    }
+    let mut event_stream = match &cli.emit_events {
+        Some(path) => Some(
+            events::EventStream::open(path)
+                .map_err(|e| Error::Usage(format!("--emit-events {}: {}", path.display(), e)))?,
+        ),
+        None => None,
+    };
+    let server = match cli.serve {
+        Some(addr) => Some(serve::ControlServer::start(addr).map_err(|e| Error::Usage(format!("--serve {}: {}", addr, e)))?),
+        None => None,
+    };
+    // Picks up edits to the config file without a restart
+    let watcher = config::config_path().and_then(|path| watch::ConfigWatcher::new(&path));
+    
+    // Screen-reader mode prints plain lines instead of drawing
+    let mut announcer = cli.a11y.then(a11y::Announcer::new);
     
-    terminal::enable_raw_mode()?;
-    execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide)?;
+    let terminal_guard = term::TerminalGuard::enter(announcer.is_none())?;
     
     let (width, height) = terminal::size()?;
     app.width = width;
     app.height = height;
     
-    let mut last_tick = Instant::now();
+    signals::install();
+    // Set when the last session ends, as opposed to quitting with `q`
+    let mut finished = false;
+    let mut last_autosave = Instant::now();
+    let mut last_status = Instant::now();
     
     loop {
         // Draw based on state
-        match app.state {
-            AppState::Setup => draw_setup(&app)?,
-            AppState::Running => draw_timer(&app)?,
-            AppState::BreakPrompt => draw_break_prompt(&app)?,
+        if let Some(announcer) = announcer.as_mut() {
+            announcer.update(&app)?;
+        } else if !fits_terminal(app.width, app.height) {
+            draw_too_small(&app)?;
+        } else {
+            match app.state {
+                AppState::Setup => draw_setup(&app)?,
+                AppState::Running => draw_timer(&app)?,
+                AppState::BreakPrompt => draw_break_prompt(&app)?,
+                AppState::WorkPrompt => draw_work_prompt(&app)?,
+                AppState::BreakLeadIn => draw_break_leadin(&app)?,
+                AppState::Ritual => draw_ritual(&app)?,
+                AppState::Stats => draw_stats(&app)?,
+                AppState::Overtime => draw_overtime(&app)?,
+            }
         }
         
-        // Handle input
+        // Handle input. The timeout also sets the frame rate: a key is
+        // answered at once, and without one the screen redraws every 100ms
         if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(KeyEvent { code, .. }) = event::read()? {
+            let event = event::read()?;
+            // Picked up by the redraw at the top of the next pass, paused or not
+            if let Event::Resize(width, height) = event {
+                app.width = width;
+                app.height = height;
+            }
+            if matches!(event, Event::Key(_)) && app.hide_clock && app.state == AppState::Running {
+                app.reveal_until = Some(Instant::now() + app.reveal_duration);
+            }
+            let key = match event {
+                Event::Key(KeyEvent { code, .. }) => Key::from_code(code),
+                _ => None,
+            };
+            // A key after an idle pause just resumes
+            let woke = key.is_some() && app.note_key(Instant::now());
+            if let Some(code) = key.filter(|_| !woke) {
                 match code {
-                    KeyCode::Char('q') => break,
-                    
-                    _ => {
-                        if app.state == AppState::Setup {
-                            match code {
-                                KeyCode::Tab | KeyCode::Down => {
-                                    app.inputs[app.focus_index].focused = false;
-                                    app.focus_index = (app.focus_index + 1) % app.inputs.len();
-                                    app.inputs[app.focus_index].focused = true;
-                                }
-                                KeyCode::Up => {
-                                    app.inputs[app.focus_index].focused = false;
-                                    app.focus_index = if app.focus_index == 0 {
-                                        app.inputs.len() - 1
-                                    } else {
-                                        app.focus_index - 1
-                                    };
-                                    app.inputs[app.focus_index].focused = true;
-                                }
-                                KeyCode::Char(c) if c.is_ascii_digit() => {
-                                    if app.inputs[app.focus_index].value.len() < 3 {
-                                        app.inputs[app.focus_index].value.push(c);
-                                    }
-                                }
-                                KeyCode::Backspace => {
-                                    app.inputs[app.focus_index].value.pop();
-                                }
-                                KeyCode::Enter => {
-                                    app.start_timer();
-                                }
-                                _ => {}
+                    _ if app.quit_pending => {
+                        if app.quit_prompt_key(code) {
+                            break;
+                        }
+                    }
+                    _ if app.command_line.is_some() => app.command_key(code),
+                    _ if app.time_edit.is_some() => app.time_edit_key(code),
+                    _ if app.label_edit.is_some() => app.label_edit_key(code),
+                    // The label takes any text, hotkeys included
+                    Key::Char(_) if app.state == AppState::Setup && app.label_focused() => app.setup_text_key(code),
+                    _ if app.skip_reason_choice.is_some() => {
+                        let effects = app.skip_reason_key(code).unwrap_or_default();
+                        if app.perform(effects) {
+                            finished = true;
+                            break;
+                        }
+                    }
+                    _ => match app.keymap.map_key(app.state, code) {
+                        Some(Action::Quit) if app.request_quit() => break,
+                        Some(Action::Quit) => {}
+                        Some(action) => {
+                            // Answering a prompt or skipping cuts the alert short
+                            if matches!(app.state, AppState::BreakPrompt | AppState::WorkPrompt) || action == Action::Skip {
+                                app.audio.stop();
                             }
-                        } else if app.state == AppState::BreakPrompt {
-                            match code {
-                                KeyCode::Enter => {
-                                    stop_sound();
-                                    app.start_break();
-                                }
-                                KeyCode::Char('s') => {
-                                    stop_sound();
-                                    // Skip break - go to next work session
-                                    app.current_session += 1;
-                                    if app.current_session > app.total_sessions {
-                                        send_notification("Pomodoro", "All sessions completed! 🎉");
-                                        break;
-                                    }
-                                    app.timer_type = TimerType::Work;
-                                    app.time_left = Duration::from_secs(app.work_minutes as u64 * 60);
-                                    app.paused = false;
-                                    app.state = AppState::Running;
-                                    app.next_break_type = None;
-                                }
-                                _ => {}
+                            let steps_on = matches!(action, Action::Start | Action::StartWork | Action::NextStep)
+                                || (app.state == AppState::BreakPrompt && action == Action::StartBreak);
+                            if steps_on {
+                                play_ui_sound(&app);
                             }
-                        } else {
-                            match code {
-                                KeyCode::Char(' ') => app.paused = !app.paused,
-                                KeyCode::Char('s') => {
-                                    stop_sound();
-                                    let should_exit = app.advance_timer();
-                                    eprintln!("   After skip: state={:?}, should_exit={}", app.state, should_exit);
-                                    if should_exit {
-                                        break;
-                                    }
-                                }
-                                KeyCode::Up => app.time_left += Duration::from_secs(60),
-                                KeyCode::Down => {
-                                    if app.time_left > Duration::from_secs(60) {
-                                        app.time_left -= Duration::from_secs(60);
-                                    }
-                                }
-                                _ => {}
+                            let effects = app.input(action);
+                            if app.perform(effects) {
+                                finished = true;
+                                break;
                             }
                         }
-                    }
+                        None if app.state == AppState::Setup => app.setup_text_key(code),
+                        None => {}
+                    },
                 }
             }
         }
         
-        // Update timer
-        if app.state == AppState::Running && !app.paused && last_tick.elapsed() >= Duration::from_secs(1) {
-            last_tick = Instant::now();
-            
-            if app.time_left > Duration::from_secs(0) {
-                app.time_left = app.time_left.saturating_sub(Duration::from_secs(1));
-            }
-            
-            if app.time_left == Duration::from_secs(0) {
-                eprintln!("⏰ Timer hit zero! Current state: {:?}, Type: {:?}", app.state, app.timer_type);
-                let should_exit = app.advance_timer();
-                eprintln!("   After advance: state={:?}, should_exit={}", app.state, should_exit);
-                if should_exit {
+        // A quit prompt left open as the session ends goes with it
+        if !matches!(app.state, AppState::Running | AppState::Overtime) {
+            app.quit_pending = false;
+        }
+        if app.state == AppState::Running {
+            app.check_auto_resume();
+            app.check_defer();
+            app.check_idle_at(Instant::now());
+        }
+        // The break doesn't start behind the skip reason picker
+        if app.state == AppState::BreakLeadIn && app.skip_reason_choice.is_none() {
+            app.check_leadin();
+        }
+        
+        if app.state == AppState::Running && !app.autosave_every.is_zero() && last_autosave.elapsed() >= app.autosave_every {
+            last_autosave = Instant::now();
+            let _ = state::save_session(&app.session_state());
+        }
+        // Every second, paused or not, so `--status` can tell a live timer
+        // from one that is gone
+        if matches!(app.state, AppState::Running | AppState::Overtime) && last_status.elapsed() >= Duration::from_secs(1) {
+            last_status = Instant::now();
+            let _ = state::save_status(&app.live_status(last_status));
+        }
+        
+        // Taken on every pass so a signal outside a countdown is dropped
+        match signals::take() {
+            Some(signals::Request::Pause) if app.state == AppState::Running && !app.paused => app.toggle_pause(),
+            Some(signals::Request::Resume) if app.state == AppState::Running && app.paused => app.toggle_pause(),
+            _ => {}
+        }
+        
+        // One request per pass, through the same actions as the keys
+        if let Some(server) = &server {
+            server.publish(serde_json::to_string(&app.status()).unwrap_or_default());
+            if let Some(action) = server.take() {
+                if action == Action::Skip {
+                    app.audio.stop();
+                }
+                let effects = app.input(action);
+                if app.perform(effects) {
+                    finished = true;
                     break;
                 }
             }
         }
+        
+        if let Some(hint) = app.audio.take_disabled_hint() {
+            app.command_message = Some(hint.to_string());
+        }
+        
+        if watcher.as_ref().is_some_and(|watcher| watcher.changed()) {
+            match config::read_config() {
+                Ok(mut new) => {
+                    override_durations(&mut new, cli);
+                    let (applied, deferred) = app.reload(&config, &new);
+                    let mut message = String::new();
+                    if !applied.is_empty() {
+                        message = format!("Config reloaded: {}", applied.join(", "));
+                    }
+                    if !deferred.is_empty() {
+                        let next = format!("{} apply on next start", deferred.join(", "));
+                        message = if message.is_empty() { next } else { format!("{} ({})", message, next) };
+                    }
+                    if !message.is_empty() {
+                        app.command_message = Some(message);
+                    }
+                    config = new;
+                }
+                // Keep the current settings; the file is probably mid-edit
+                Err(e) => app.command_message = Some(format!("Config not reloaded: {}", e)),
+            }
+        }
+        
+        // Update timer
+        let effects = app.sync_clock(Instant::now());
+        if app.perform(effects) {
+            finished = true;
+            break;
+        }
+        if let Some(stream) = event_stream.as_mut() {
+            stream.observe(&app);
+        }
+    }
+    if let Some(stream) = event_stream {
+        stream.close(&app, finished);
+    }
+    
+    drop(terminal_guard);
+    // Quitting mid-alert doesn't leave the player running, but the sound
+    // that ends a --once run gets to play
+    if finished && app.once {
+        app.audio.finish(Duration::from_secs(10));
     }
+    app.audio.stop();
     
-    execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen)?;
-    terminal::disable_raw_mode()?;
+    // Mute and volume set from the keyboard carry over to the next launch
+    if app.muted != config.muted {
+        if let Err(e) = config::save_setting("muted", app.muted) {
+            eprintln!("⚠ Could not save mute setting: {}", e);
+        }
+    }
+    if app.volume != config.volume {
+        if let Err(e) = config::save_setting("volume", volume_percent(app.volume) as f64 / 100.0) {
+            eprintln!("⚠ Could not save volume: {}", e);
+        }
+    }
     
-    println!("\n✓ Pomodoro session completed!\n");
+    let _ = state::clear_status();
+    if finished && app.once {
+        println!("\n✓ Focus session complete\n");
+    } else {
+        println!("\n✓ Pomodoro session completed!\n");
+    }
+    // A quit keeps the snapshot around to resume from, brought up to date
+    if finished {
+        let _ = state::clear_session();
+    } else if app.state == AppState::Running {
+        let _ = state::save_session(&app.session_state());
+    }
+    if finished && app.ask_next_action {
+        ask_next_action()?;
+    }
     Ok(())
 }
 
-fn main() -> io::Result<()> {
-    run_app()
+// Duration flags beat the config file
+fn override_durations(config: &mut Config, cli: &cli::Cli) {
+    config.work_minutes = cli.work.unwrap_or(config.work_minutes);
+    config.short_break_minutes = cli.short_break.unwrap_or(config.short_break_minutes);
+    config.long_break_minutes = cli.long_break.unwrap_or(config.long_break_minutes);
+    config.total_sessions = cli.sessions.unwrap_or(config.total_sessions);
+    if cli.once {
+        config.total_sessions = 1;
+    }
+}
+
+// GTD-style: decide the next step now, see it at the next launch
+fn ask_next_action() -> error::Result<()> {
+    eprint!("What's your next action? (ENTER to skip) ");
+    io::stderr().flush()?;
+    
+    // Skipping also drops the previous plan, which is done by now
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    state::save_next_action(&answer)?;
+    if !answer.trim().is_empty() {
+        println!("✓ Saved, it will be shown next time");
+    }
+    Ok(())
+}
+
+fn confirm(question: &str) -> io::Result<bool> {
+    eprint!("{} [y/N] ", question);
+    io::stderr().flush()?;
+    
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(answer.trim().eq_ignore_ascii_case("y"))
+}
+
+// Empty input keeps `current`, "-" clears it
+fn prompt_field(name: &str, current: &str) -> io::Result<Option<String>> {
+    eprint!("{} [{}]: ", name, if current.is_empty() { "-" } else { current });
+    io::stderr().flush()?;
+    
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(match answer.trim() {
+        "" => Some(current.to_string()),
+        "-" => None,
+        value => Some(value.to_string()),
+    })
+}
+
+fn edit_last_record() -> error::Result<()> {
+    let Some(mut entry) = history::load_records().pop() else {
+        println!("No sessions yet");
+        return Ok(());
+    };
+    
+    println!(
+        "Last session: {}  •  {}  •  session {}{}",
+        entry.timestamp.format("%Y-%m-%d %H:%M"),
+        stats::format_duration(entry.duration_secs),
+        entry.session,
+        entry.task.as_ref().map(|task| format!("  •  {}", task)).unwrap_or_default()
+    );
+    println!("ENTER keeps a value, \"-\" clears it.");
+    
+    entry.tag = prompt_field("Tag", entry.tag.as_deref().unwrap_or(""))?.filter(|tag| !tag.is_empty());
+    
+    let rating = entry.rating.map(|r| r.to_string()).unwrap_or_default();
+    entry.rating = match prompt_field("Rating (1-5)", &rating)? {
+        Some(value) if value.is_empty() => None,
+        Some(value) => match value.parse::<u8>() {
+            Ok(r) if (1..=5).contains(&r) => Some(r),
+            _ => {
+                eprintln!("✗ Rating must be 1-5, keeping {}", if rating.is_empty() { "-" } else { &rating });
+                entry.rating
+            }
+        },
+        None => None,
+    };
+    
+    let interruptions = entry.interruptions.to_string();
+    entry.interruptions = match prompt_field("Interruptions", &interruptions)? {
+        Some(value) => value.parse().unwrap_or_else(|_| {
+            eprintln!("✗ Not a number, keeping {}", interruptions);
+            entry.interruptions
+        }),
+        None => 0,
+    };
+    
+    history::replace_last_record(&entry).map_err(Error::History)?;
+    println!("✓ Session updated");
+    Ok(())
+}
+
+fn confirm_second_instance(pid: u32) -> io::Result<bool> {
+    eprintln!("⚠ Another pomodoro instance is already running (PID {}).", pid);
+    eprintln!("  Both would send notifications and write to the same history.");
+    confirm("  Continue anyway?")
+}
+
+fn manage_history(cli: &cli::Cli) -> error::Result<()> {
+    if cli.reset_history {
+        match history::archive_history().map_err(Error::History)? {
+            Some(archive) => println!("✓ History archived to {}", archive.display()),
+            None => println!("No history to archive"),
+        }
+    }
+    if cli.clear_history {
+        if !cli.yes && !confirm("Permanently delete all session history?")? {
+            println!("History kept");
+            return Ok(());
+        }
+        if history::clear_history().map_err(Error::History)? {
+            println!("✓ History cleared");
+        } else {
+            println!("No history to clear");
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("✗ {}", e);
+            if matches!(e, Error::Usage(_)) {
+                eprintln!("\n{}", cli::USAGE);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> error::Result<()> {
+    let cli = cli::parse_args(std::env::args().skip(1)).map_err(Error::Usage)?;
+    if let Some(path) = &cli.debug {
+        log::init(path).map_err(|e| Error::Usage(format!("--debug {}: {}", path.display(), e)))?;
+    }
+    if cli.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+    if cli.status {
+        println!("{}", state::format_status(state::load_status().as_ref(), Local::now().timestamp()));
+        return Ok(());
+    }
+    if cli.stats {
+        for line in stats::stats_lines(&history::load_records()) {
+            println!("{}", line);
+        }
+        return Ok(());
+    }
+    if let Some(path) = &cli.export_csv {
+        let records = history::load_records();
+        export::write_csv(path, &records).map_err(Error::History)?;
+        println!("✓ Exported {} session{} to {}", records.len(), if records.len() == 1 { "" } else { "s" }, path.display());
+        return Ok(());
+    }
+    if cli.reset_history || cli.clear_history {
+        return manage_history(&cli);
+    }
+    if cli.edit_last {
+        return edit_last_record();
+    }
+    if let Some((title, body)) = &cli.test_notify {
+        notifier::show(title, body)?;
+        println!("✓ Notification sent");
+        return Ok(());
+    }
+    if cli.selftest {
+        return selftest::run();
+    }
+    if cli.review {
+        let config = config::load_config();
+        let theme = theme::load(&config.theme, &config.colors);
+        return Ok(review::run(&history::load_records(), &theme)?);
+    }
+    
+    // Continuing anyway leaves the lock with the first instance
+    let _lock = match lock::acquire() {
+        Ok(LockStatus::Acquired(lock)) => Some(lock),
+        Ok(LockStatus::Running(pid)) => {
+            if !confirm_second_instance(pid)? {
+                return Ok(());
+            }
+            None
+        }
+        Err(_) => None,
+    };
+    
+    run_app(&cli)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_label_placeholders() {
+        assert_eq!(session_label("WORK SESSION {current}/{total}", 2, 4), "WORK SESSION 2/4");
+        assert_eq!(session_label("Pomodoro #{index}, {remaining} to go", 2, 4), "Pomodoro #1, 2 to go");
+        assert_eq!(session_label("Session {current}", 3, 4), "Session 3");
+    }
+
+    #[test]
+    fn setup_fits_the_minimum_size_when_compact() {
+        // Six inputs, the task label and the task picker
+        assert!(setup_rows(8, true) <= MIN_HEIGHT);
+        assert!(setup_rows(8, false) > 24);
+    }
+
+    #[test]
+    fn minimum_terminal_size() {
+        assert!(fits_terminal(50, 15));
+        assert!(fits_terminal(200, 60));
+        assert!(!fits_terminal(49, 15));
+        assert!(!fits_terminal(50, 14));
+    }
+
+    #[test]
+    fn colon_only_blinks_while_counting() {
+        let mut app = PomodoroApp::new(&Config { blink_colon: true, ..Config::default() });
+        app.start_timer();
+        assert!(colon_visible(&app, 1498));
+        assert!(!colon_visible(&app, 1499));
+        app.toggle_pause();
+        assert!(colon_visible(&app, 1499));
+    }
+
+    #[test]
+    fn hidden_colon_keeps_clock_width() {
+        let shown = render_big_time(754, true, BLOCK_FONT);
+        let hidden = render_big_time(754, false, BLOCK_FONT);
+        for (a, b) in shown.iter().zip(&hidden) {
+            assert_eq!(a.chars().count(), b.chars().count());
+        }
+        assert_ne!(shown, hidden);
+    }
+
+    #[test]
+    fn every_font_renders_the_clock_evenly() {
+        for (name, glyphs) in FONTS {
+            let lines = render_big_time(12 * 60 + 34, true, glyphs);
+            assert_eq!(lines.len(), 5, "{}", name);
+            assert!(lines.iter().all(|line| line.chars().count() == 5 * 7), "{}", name);
+        }
+        assert_eq!(font("nonsense"), BLOCK_FONT);
+    }
+
+    #[test]
+    fn big_text_blanks_unknown_characters() {
+        let word = render_big_text("Break", BLOCK_FONT);
+        assert_eq!(word, render_big_text("BREAK", BLOCK_FONT));
+        assert!(word.iter().all(|line| line.chars().count() == 5 * 7));
+        
+        let unknown = render_big_text("?", BLOCK_FONT);
+        assert!(unknown.iter().all(|line| line.trim().is_empty() && line.chars().count() == 7));
+    }
+
+    #[test]
+    fn progress_bar_fills_and_clamps() {
+        let bar = |elapsed: u64, total: u64| render_progress_bar(Duration::from_secs(elapsed), Duration::from_secs(total), 10, false);
+        assert_eq!(bar(600, 1500), "[████░░░░░░]");
+        assert_eq!(bar(0, 1500), "[░░░░░░░░░░]");
+        assert_eq!(bar(2000, 1500), "[██████████]");
+        assert_eq!(bar(0, 0), "[░░░░░░░░░░]");
+    }
+
+    #[test]
+    fn smooth_progress_bar_moves_in_eighths() {
+        let bar = |elapsed: Duration| render_progress_bar(elapsed, Duration::from_secs(80), 10, true);
+        assert_eq!(bar(Duration::ZERO), "[░░░░░░░░░░]");
+        assert_eq!(bar(Duration::from_millis(1500)), "[▏░░░░░░░░░]");
+        assert_eq!(bar(Duration::from_secs(36)), "[████▌░░░░░]");
+        assert_eq!(bar(Duration::from_secs(80)), "[██████████]");
+        assert_eq!(bar(Duration::from_millis(37_250)).chars().count(), 12);
+    }
 }
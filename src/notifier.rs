@@ -0,0 +1,88 @@
+use crate::error::{Error, Result};
+use crate::log;
+use std::sync::{Arc, Mutex};
+
+// Where notifications go. The timer only says what to announce; the
+// notifier decides how, so tests and embedders can swap it out
+pub trait Notifier: Send {
+    fn notify(&self, title: &str, body: &str);
+}
+
+// The system's notification center
+pub struct DesktopNotifier;
+
+impl Notifier for DesktopNotifier {
+    // A missed notification is not worth stopping the timer for
+    fn notify(&self, title: &str, body: &str) {
+        if let Err(e) = show(title, body) {
+            log::debug(&format!("✗ {}", e));
+        }
+    }
+}
+
+// Drops everything
+pub struct NullNotifier;
+
+impl Notifier for NullNotifier {
+    fn notify(&self, _title: &str, _body: &str) {}
+}
+
+// Keeps what would have been shown. Clones share the record, so keep one
+// to read back after boxing the other
+#[derive(Clone, Default)]
+pub struct RecordingNotifier {
+    sent: Arc<Mutex<Vec<(String, String)>>>,
+}
+
+impl RecordingNotifier {
+    // (title, body) pairs, oldest first
+    pub fn sent(&self) -> Vec<(String, String)> {
+        self.sent.lock().map(|sent| sent.clone()).unwrap_or_default()
+    }
+}
+
+impl Notifier for RecordingNotifier {
+    fn notify(&self, title: &str, body: &str) {
+        if let Ok(mut sent) = self.sent.lock() {
+            sent.push((title.to_string(), body.to_string()));
+        }
+    }
+}
+
+pub fn show(title: &str, message: &str) -> Result<()> {
+    #[cfg(not(target_os = "windows"))]
+    {
+        notify_rust::Notification::new()
+            .summary(title)
+            .body(message)
+            .show()
+            .map(|_| ())
+            .map_err(|e| Error::Notification(e.to_string()))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::process::Command;
+        Command::new("powershell")
+            .args(&["-Command", &format!("
+                [Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] | Out-Null;
+                $Template = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02);
+                $RawXml = [xml] $Template.GetXml();
+                ($RawXml.toast.visual.binding.text|where {{$_.id -eq '1'}}).AppendChild($RawXml.CreateTextNode('{}')) | Out-Null;
+                ($RawXml.toast.visual.binding.text|where {{$_.id -eq '2'}}).AppendChild($RawXml.CreateTextNode('{}')) | Out-Null;
+                $SerializedXml = New-Object Windows.Data.Xml.Dom.XmlDocument;
+                $SerializedXml.LoadXml($RawXml.OuterXml);
+                $Toast = [Windows.UI.Notifications.ToastNotification]::new($SerializedXml);
+                [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('Pomodoro').Show($Toast);
+            ", title, message)])
+            .output()
+            .map_err(|e| Error::Notification(e.to_string()))
+            .and_then(|output| {
+                if output.status.success() {
+                    Ok(())
+                } else {
+                    Err(Error::Notification(String::from_utf8_lossy(&output.stderr).trim().to_string()))
+                }
+            })
+    }
+}
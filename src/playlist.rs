@@ -0,0 +1,123 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "wav", "ogg", "flac", "m4a", "opus"];
+const PLAYLIST_EXTENSIONS: &[&str] = &["m3u", "m3u8", "txt"];
+
+// Tracks to pick the completion sound from: a single file, every audio
+// file in a directory, or the entries of an .m3u playlist
+pub struct Playlist {
+    tracks: Vec<PathBuf>,
+    rng: u64,
+}
+
+impl Playlist {
+    // A fixed seed makes the sequence of picks reproducible. No path, or
+    // one that doesn't exist, means silence
+    pub fn load(path: Option<&Path>, seed: Option<u64>) -> Self {
+        let seed = seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(0)
+        });
+        Self {
+            tracks: path.map(scan).unwrap_or_default(),
+            // xorshift gets stuck on zero
+            rng: seed | 1,
+        }
+    }
+
+    // None for an empty directory or playlist, which means silence
+    pub fn pick(&mut self) -> Option<PathBuf> {
+        if self.tracks.is_empty() {
+            return None;
+        }
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        let index = (self.rng % self.tracks.len() as u64) as usize;
+        Some(self.tracks[index].clone())
+    }
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+fn scan(path: &Path) -> Vec<PathBuf> {
+    if path.is_dir() {
+        let mut tracks: Vec<PathBuf> = fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .filter_map(Result::ok)
+                    .map(|entry| entry.path())
+                    .filter(|p| p.is_file() && has_extension(p, AUDIO_EXTENSIONS))
+                    .collect()
+            })
+            .unwrap_or_default();
+        // read_dir order is platform-dependent; sort so seeds are portable
+        tracks.sort();
+        tracks
+    } else if has_extension(path, PLAYLIST_EXTENSIONS) {
+        let base = path.parent().unwrap_or(Path::new("."));
+        fs::read_to_string(path)
+            .map(|text| parse_playlist(&text, base))
+            .unwrap_or_default()
+    } else if path.is_file() {
+        vec![path.to_path_buf()]
+    } else {
+        Vec::new()
+    }
+}
+
+// Relative entries are resolved against the playlist's own directory
+fn parse_playlist(text: &str, base: &Path) -> Vec<PathBuf> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| base.join(line))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn playlist_skips_comments_and_resolves_relative_paths() {
+        let text = "#EXTM3U\n#EXTINF:3,Bell\nbell.mp3\n\n/abs/chime.wav\n";
+        let tracks = parse_playlist(text, Path::new("/music"));
+        assert_eq!(
+            tracks,
+            vec![PathBuf::from("/music/bell.mp3"), PathBuf::from("/abs/chime.wav")]
+        );
+    }
+
+    #[test]
+    fn same_seed_picks_same_tracks() {
+        let tracks: Vec<PathBuf> = (0..5).map(|i| PathBuf::from(format!("{}.mp3", i))).collect();
+        let mut a = Playlist { tracks: tracks.clone(), rng: 42 };
+        let mut b = Playlist { tracks: tracks.clone(), rng: 42 };
+        for _ in 0..10 {
+            let pick = a.pick();
+            assert_eq!(pick, b.pick());
+            assert!(tracks.contains(&pick.unwrap()));
+        }
+    }
+
+    #[test]
+    fn empty_playlist_is_silent() {
+        let mut empty = Playlist { tracks: Vec::new(), rng: 1 };
+        assert_eq!(empty.pick(), None);
+    }
+
+    #[test]
+    fn unset_or_missing_sound_is_silent() {
+        assert_eq!(Playlist::load(None, Some(1)).pick(), None);
+        assert_eq!(Playlist::load(Some(Path::new("/nonexistent/alert.mp3")), Some(1)).pick(), None);
+    }
+}
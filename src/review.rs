@@ -0,0 +1,115 @@
+use pomodoro_timer::history::HistoryEntry;
+use pomodoro_timer::stats::format_duration;
+use pomodoro_timer::theme::Theme;
+use pomodoro_timer::truncate;
+use crate::term::TerminalGuard;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute, queue,
+    style::{Attribute, Print, ResetColor, SetAttribute, SetForegroundColor},
+    terminal::{self, ClearType},
+};
+use std::io::{self, Write};
+
+// PgUp/PgDn step this many sessions at once
+const PAGE: usize = 10;
+
+// Read-only browser over the history, one session per screen, newest first
+pub fn run(records: &[HistoryEntry], theme: &Theme) -> io::Result<()> {
+    if records.is_empty() {
+        println!("No sessions yet");
+        return Ok(());
+    }
+
+    let _guard = TerminalGuard::enter(true)?;
+    browse(records, theme)
+}
+
+fn browse(records: &[HistoryEntry], theme: &Theme) -> io::Result<()> {
+    let last = records.len() - 1;
+    // Position 0 is the newest record
+    let mut position = 0;
+    loop {
+        draw(&records[last - position], position, records.len(), theme)?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            position = match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Right | KeyCode::Down | KeyCode::Char('j') => (position + 1).min(last),
+                KeyCode::Left | KeyCode::Up | KeyCode::Char('k') => position.saturating_sub(1),
+                KeyCode::PageDown => (position + PAGE).min(last),
+                KeyCode::PageUp => position.saturating_sub(PAGE),
+                KeyCode::Home => 0,
+                KeyCode::End => last,
+                _ => position,
+            };
+        }
+    }
+}
+
+fn details(record: &HistoryEntry) -> Vec<(&'static str, String)> {
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    vec![
+        ("Date", record.timestamp.format("%Y-%m-%d %H:%M").to_string()),
+        ("Duration", format_duration(record.duration_secs)),
+        ("Overtime", format_duration(record.overtime_secs)),
+        ("Session", record.session.to_string()),
+        ("Task", optional(record.task.clone())),
+        ("Label", optional(record.label.clone())),
+        ("Tag", optional(record.tag.clone())),
+        ("Rating", optional(record.rating.map(|r| format!("{}/5", r)))),
+        ("Interruptions", record.interruptions.to_string()),
+        ("Pauses", format!("{} ({})", record.pauses, format_duration(record.paused_secs))),
+        ("Ended", match (record.skipped, record.counted) {
+            (false, _) => "completed",
+            (true, true) => "skipped",
+            (true, false) => "skipped, not counted",
+        }.to_string()),
+    ]
+}
+
+fn draw(record: &HistoryEntry, position: usize, count: usize, theme: &Theme) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    let (width, height) = terminal::size()?;
+    execute!(stdout, terminal::Clear(ClearType::All))?;
+
+    let lines = details(record);
+    let start_row = (height / 2).saturating_sub(lines.len() as u16 / 2 + 2);
+
+    let title = format!("SESSION {} OF {}", position + 1, count);
+    queue!(
+        stdout,
+        cursor::MoveTo((width / 2).saturating_sub((title.len() / 2) as u16), start_row),
+        SetForegroundColor(theme.title.into()),
+        SetAttribute(Attribute::Bold),
+        Print(&title),
+        SetAttribute(Attribute::Reset)
+    )?;
+
+    let col = (width / 2).saturating_sub(20);
+    for (i, (label, value)) in lines.iter().enumerate() {
+        queue!(
+            stdout,
+            cursor::MoveTo(col, start_row + 2 + i as u16),
+            SetForegroundColor(theme.help.into()),
+            Print(format!("{:<15}", label)),
+            SetForegroundColor(theme.text.into()),
+            Print(truncate(value, width.saturating_sub(col + 15) as usize))
+        )?;
+    }
+
+    let help = truncate("[←] Newer  •  [→] Older  •  [PgUp/PgDn] ±10  •  [Home/End] Newest/Oldest  •  [q] Quit", width as usize);
+    queue!(
+        stdout,
+        cursor::MoveTo((width / 2).saturating_sub((help.chars().count() / 2) as u16), start_row + 3 + lines.len() as u16),
+        SetForegroundColor(theme.help.into()),
+        Print(help),
+        ResetColor
+    )?;
+
+    stdout.flush()
+}
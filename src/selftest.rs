@@ -0,0 +1,83 @@
+use crate::alert::RecordingAlerts;
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::history;
+use crate::notifier::RecordingNotifier;
+use crate::{AppState, PomodoroApp, TimerType};
+use std::fs;
+
+// A shortened two-session run through the same state machine as the TUI,
+// with the countdown ticked as fast as possible. History, state and cache
+// files go to a scratch directory so the user's own are never touched
+pub fn run() -> Result<()> {
+    let dir = std::env::temp_dir().join(format!("pomodoro-selftest-{}", std::process::id()));
+    fs::create_dir_all(&dir).map_err(Error::History)?;
+    for var in ["XDG_DATA_HOME", "XDG_STATE_HOME", "XDG_CACHE_HOME"] {
+        std::env::set_var(var, &dir);
+    }
+
+    let result = run_cycle();
+    let _ = fs::remove_dir_all(&dir);
+    result
+}
+
+fn run_cycle() -> Result<()> {
+    let mut app = PomodoroApp::new(&Config::default());
+    let notifier = RecordingNotifier::default();
+    app.notifier = Box::new(notifier.clone());
+    // Alerts are recorded instead of played
+    let alerts = RecordingAlerts::default();
+    app.alerts = Box::new(alerts.clone());
+    for (input, value) in app.inputs.iter_mut().zip(["1", "1", "1", "2"]) {
+        input.value = value.to_string();
+    }
+    app.start_timer();
+    check("timer starts", app.state == AppState::Running && app.timer_type == TimerType::Work)?;
+
+    check("work session 1 ends at the break prompt", run_down(&mut app) == Some(false) && app.state == AppState::BreakPrompt)?;
+    check("short break comes next", app.next_break_type == Some(TimerType::ShortBreak))?;
+    app.confirm_break();
+    check("break starts", app.state == AppState::Running && app.timer_type == TimerType::ShortBreak)?;
+
+    check("break ends in work session 2", run_down(&mut app) == Some(false) && app.current_session == 2)?;
+    check("last work session ends the run", run_down(&mut app) == Some(true))?;
+
+    let records = history::load_records();
+    check("two sessions in history", records.len() == 2)?;
+    check(
+        "history records are complete",
+        records.iter().zip(1..).all(|(r, n)| r.session == n && r.duration_secs == 60 && !r.skipped && r.counted),
+    )?;
+
+    check("two completion alerts", alerts.alerts().len() == 2)?;
+    check(
+        "run-complete notification",
+        notifier.sent().last().is_some_and(|(_, body)| body.ends_with("Completed 2 pomodoros — 2 focus minutes")),
+    )?;
+
+    println!("✓ Self-test passed");
+    Ok(())
+}
+
+// Ticks until the current countdown runs out; Some(true) if that ended the
+// run, None if it never did
+fn run_down(app: &mut PomodoroApp) -> Option<bool> {
+    let timer_type = app.timer_type;
+    for _ in 0..=app.time_left.as_secs() {
+        let effects = app.tick();
+        let finished = app.perform(effects);
+        if finished || app.state != AppState::Running || app.timer_type != timer_type {
+            return Some(finished);
+        }
+    }
+    None
+}
+
+fn check(name: &str, ok: bool) -> Result<()> {
+    if ok {
+        println!("✓ {}", name);
+        Ok(())
+    } else {
+        Err(Error::SelfTest(name.to_string()))
+    }
+}
@@ -0,0 +1,117 @@
+use pomodoro_timer::Action;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// HTTP control for stream decks and home automation. Connections are
+// answered one at a time on a background thread; actions go to the main
+// loop over a channel and are taken on its next pass, like signals
+pub struct ControlServer {
+    state: Arc<Mutex<String>>,
+    actions: Receiver<Action>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Route {
+    State,
+    Act(Action),
+    MethodNotAllowed,
+    NotFound,
+}
+
+impl ControlServer {
+    // Loopback addresses only: anyone who can connect can drive the timer
+    pub fn start(addr: SocketAddr) -> io::Result<Self> {
+        if !addr.ip().is_loopback() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "only localhost addresses are allowed"));
+        }
+        let listener = TcpListener::bind(addr)?;
+        let state = Arc::new(Mutex::new("{}".to_string()));
+        let (tx, actions) = mpsc::channel();
+        let shared = Arc::clone(&state);
+        thread::spawn(move || {
+            for stream in listener.incoming().filter_map(Result::ok) {
+                let _ = handle(stream, &shared, &tx);
+            }
+        });
+        Ok(Self { state, actions })
+    }
+
+    // The JSON GET /state answers with until the next call
+    pub fn publish(&self, json: String) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = json;
+        }
+    }
+
+    pub fn take(&self) -> Option<Action> {
+        self.actions.try_recv().ok()
+    }
+}
+
+fn route(request_line: &str) -> Route {
+    let mut parts = request_line.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/state")) => Route::State,
+        (Some("POST"), Some("/pause")) => Route::Act(Action::TogglePause),
+        (Some("POST"), Some("/skip")) => Route::Act(Action::Skip),
+        (Some("POST"), Some("/reset")) => Route::Act(Action::Restart),
+        (_, Some("/state" | "/pause" | "/skip" | "/reset")) => Route::MethodNotAllowed,
+        _ => Route::NotFound,
+    }
+}
+
+fn handle(stream: TcpStream, state: &Mutex<String>, actions: &Sender<Action>) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Headers are read but not used; closing with them unread would reset
+    // the connection before the client sees the response
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let (status, body) = match route(&request_line) {
+        Route::State => ("200 OK", state.lock().map(|state| state.clone()).unwrap_or_default()),
+        Route::Act(action) => {
+            let _ = actions.send(action);
+            ("202 Accepted", "{\"accepted\":true}".to_string())
+        }
+        Route::MethodNotAllowed => ("405 Method Not Allowed", "{\"error\":\"method not allowed\"}".to_string()),
+        Route::NotFound => ("404 Not Found", "{\"error\":\"not found\"}".to_string()),
+    };
+    write!(
+        &stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_map_to_actions() {
+        assert_eq!(route("GET /state HTTP/1.1\r\n"), Route::State);
+        assert_eq!(route("POST /pause HTTP/1.1\r\n"), Route::Act(Action::TogglePause));
+        assert_eq!(route("POST /skip HTTP/1.1\r\n"), Route::Act(Action::Skip));
+        assert_eq!(route("POST /reset HTTP/1.1\r\n"), Route::Act(Action::Restart));
+        assert_eq!(route("GET /pause HTTP/1.1\r\n"), Route::MethodNotAllowed);
+        assert_eq!(route("GET /favicon.ico HTTP/1.1\r\n"), Route::NotFound);
+        assert_eq!(route(""), Route::NotFound);
+    }
+
+    #[test]
+    fn only_localhost_is_served() {
+        let err = ControlServer::start("0.0.0.0:0".parse().unwrap()).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
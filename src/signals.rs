@@ -0,0 +1,57 @@
+// External pause control: SIGUSR1 pauses, SIGUSR2 resumes. The handlers
+// only raise a flag; the main loop picks it up on its next pass
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Request {
+    Pause,
+    Resume,
+}
+
+#[cfg(unix)]
+mod imp {
+    use super::Request;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    static PAUSE: AtomicBool = AtomicBool::new(false);
+    static RESUME: AtomicBool = AtomicBool::new(false);
+
+    extern "C" fn on_pause(_: libc::c_int) {
+        PAUSE.store(true, Ordering::SeqCst);
+    }
+
+    extern "C" fn on_resume(_: libc::c_int) {
+        RESUME.store(true, Ordering::SeqCst);
+    }
+
+    pub fn install() {
+        unsafe {
+            libc::signal(libc::SIGUSR1, on_pause as extern "C" fn(libc::c_int) as libc::sighandler_t);
+            libc::signal(libc::SIGUSR2, on_resume as extern "C" fn(libc::c_int) as libc::sighandler_t);
+        }
+    }
+
+    // When both arrived since the last check, the resume wins
+    pub fn take() -> Option<Request> {
+        let pause = PAUSE.swap(false, Ordering::SeqCst);
+        let resume = RESUME.swap(false, Ordering::SeqCst);
+        if resume {
+            Some(Request::Resume)
+        } else if pause {
+            Some(Request::Pause)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    use super::Request;
+
+    pub fn install() {}
+
+    pub fn take() -> Option<Request> {
+        None
+    }
+}
+
+pub use imp::{install, take};
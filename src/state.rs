@@ -0,0 +1,271 @@
+use crate::TimerType;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+// Snapshot of the countdown in progress, written periodically so a crash
+// loses at most one autosave interval
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionState {
+    pub timer_type: TimerType,
+    pub current_session: u32,
+    pub total_sessions: u32,
+    pub remaining_secs: u64,
+    pub work_secs: u64,
+    pub short_break_secs: u64,
+    pub long_break_secs: u64,
+    pub paused: bool,
+    // Where the run stands in its long-break cycle. Missing from older
+    // snapshots; the interval then comes from the config
+    #[serde(default)]
+    pub long_break_interval: Option<u32>,
+    #[serde(default)]
+    pub work_since_long_break: u32,
+    #[serde(default)]
+    pub completed_work_sessions: u32,
+    #[serde(default)]
+    pub long_breaks_taken: usize,
+}
+
+// How long a paused timer can be resumed: the same day, not days later
+const PAUSED_RESUME_SECS: u64 = 12 * 60 * 60;
+
+// How old a status snapshot may get before `--status` calls the timer gone.
+// The TUI rewrites it every second, paused or not
+const STATUS_STALE_SECS: i64 = 10;
+
+// What `--status` reads for a tmux or waybar status line
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LiveStatus {
+    pub timer_type: TimerType,
+    pub current_session: u32,
+    pub total_sessions: u32,
+    pub remaining_secs: u64,
+    // Counting up past a finished work session
+    #[serde(default)]
+    pub overtime_secs: Option<u64>,
+    pub paused: bool,
+    // Unix seconds when it was written
+    pub updated: i64,
+}
+
+// ~/.local/state/pomodoro/next_action, honouring $XDG_STATE_HOME
+pub fn next_action_path() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_STATE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".local").join("state"),
+    };
+    Some(base.join("pomodoro").join("next_action"))
+}
+
+// The action planned at the end of the last run, if any
+pub fn load_next_action() -> Option<String> {
+    let text = fs::read_to_string(next_action_path()?).ok()?;
+    Some(text.trim().to_string()).filter(|action| !action.is_empty())
+}
+
+// An empty action forgets the previous one
+pub fn save_next_action(action: &str) -> io::Result<()> {
+    let path = next_action_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory"))?;
+    let action = action.trim();
+    if action.is_empty() {
+        return match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, format!("{}\n", action))
+}
+
+// ~/.cache/pomodoro/NAME, honouring $XDG_CACHE_HOME
+fn cache_path(name: &str) -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CACHE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".cache"),
+    };
+    Some(base.join("pomodoro").join(name))
+}
+
+pub fn session_path() -> Option<PathBuf> {
+    cache_path("session.json")
+}
+
+pub fn status_path() -> Option<PathBuf> {
+    cache_path("state.json")
+}
+
+// Temp file plus rename, so a crash mid-write keeps the previous snapshot
+// and a reader never sees half a file
+fn write_json<T: Serialize>(path: Option<PathBuf>, value: &T) -> io::Result<()> {
+    let path = path.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no home directory"))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(value)?)?;
+    fs::rename(&tmp, &path)
+}
+
+fn remove(path: Option<PathBuf>) -> io::Result<()> {
+    match path.map(fs::remove_file) {
+        Some(Err(e)) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+pub fn save_session(session: &SessionState) -> io::Result<()> {
+    write_json(session_path(), session)
+}
+
+// The snapshot and when it was last written
+pub fn load_session() -> Option<(SessionState, SystemTime)> {
+    let path = session_path()?;
+    let written = fs::metadata(&path).and_then(|meta| meta.modified()).ok()?;
+    let session = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+    Some((session, written))
+}
+
+// Seconds left to resume with. A running countdown went on after the
+// snapshot was written and may have run out since (None); a paused one
+// stood still, but one left longer than PAUSED_RESUME_SECS is dropped too
+pub fn time_to_resume(session: &SessionState, written: SystemTime, now: SystemTime) -> Option<u64> {
+    let age = now.duration_since(written).unwrap_or_default().as_secs();
+    let since = if !session.paused {
+        age
+    } else if age <= PAUSED_RESUME_SECS {
+        0
+    } else {
+        return None;
+    };
+    session.remaining_secs.checked_sub(since).filter(|left| *left > 0)
+}
+
+// Called once the run is over; nothing is left to resume
+pub fn clear_session() -> io::Result<()> {
+    remove(session_path())
+}
+
+pub fn save_status(status: &LiveStatus) -> io::Result<()> {
+    write_json(status_path(), status)
+}
+
+// Called when the TUI exits, so the status line goes idle straight away
+pub fn clear_status() -> io::Result<()> {
+    remove(status_path())
+}
+
+pub fn load_status() -> Option<LiveStatus> {
+    serde_json::from_str(&fs::read_to_string(status_path()?).ok()?).ok()
+}
+
+// "🍅 12:34 WORK 2/4", or "idle" with no timer running. `now` is in Unix
+// seconds
+pub fn format_status(status: Option<&LiveStatus>, now: i64) -> String {
+    let Some(status) = status.filter(|s| now - s.updated <= STATUS_STALE_SECS) else {
+        return "idle".to_string();
+    };
+    let (icon, kind) = match status.timer_type {
+        TimerType::Work => ("🍅", "WORK"),
+        TimerType::ShortBreak => ("☕", "BREAK"),
+        TimerType::LongBreak => ("☕", "LONG BREAK"),
+    };
+    let time = match status.overtime_secs {
+        Some(secs) => format!("+{:02}:{:02}", secs / 60, secs % 60),
+        None => format!("{:02}:{:02}", status.remaining_secs / 60, status.remaining_secs % 60),
+    };
+    let mut line = format!("{} {} {} {}/{}", icon, time, kind, status.current_session, status.total_sessions);
+    if status.paused {
+        line.push_str(" ⏸");
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_state_round_trips() {
+        let session = SessionState {
+            timer_type: TimerType::LongBreak,
+            current_session: 4,
+            total_sessions: 4,
+            remaining_secs: 512,
+            work_secs: 1500,
+            short_break_secs: 300,
+            long_break_secs: 900,
+            paused: true,
+            long_break_interval: Some(2),
+            work_since_long_break: 0,
+            completed_work_sessions: 4,
+            long_breaks_taken: 2,
+        };
+        let text = serde_json::to_string(&session).unwrap();
+        assert!(text.contains("\"long_break\""));
+        assert_eq!(serde_json::from_str::<SessionState>(&text).unwrap(), session);
+    }
+
+    #[test]
+    fn older_snapshots_still_load() {
+        // Written before the long-break cycle was saved
+        let text = r#"{"timer_type":"work","current_session":2,"total_sessions":4,"remaining_secs":600,
+            "work_secs":1500,"short_break_secs":300,"long_break_secs":900,"paused":false}"#;
+        let session: SessionState = serde_json::from_str(text).unwrap();
+        assert_eq!(session.long_break_interval, None);
+        assert_eq!((session.work_since_long_break, session.completed_work_sessions, session.long_breaks_taken), (0, 0, 0));
+    }
+
+    #[test]
+    fn only_unfinished_sessions_resume() {
+        let mut session = SessionState {
+            timer_type: TimerType::Work,
+            current_session: 2,
+            total_sessions: 4,
+            remaining_secs: 600,
+            work_secs: 1500,
+            short_break_secs: 300,
+            long_break_secs: 900,
+            paused: false,
+            long_break_interval: Some(4),
+            work_since_long_break: 1,
+            completed_work_sessions: 1,
+            long_breaks_taken: 0,
+        };
+        let written = SystemTime::UNIX_EPOCH;
+        let later = |secs: u64| written + std::time::Duration::from_secs(secs);
+        assert_eq!(time_to_resume(&session, written, later(120)), Some(480));
+        assert_eq!(time_to_resume(&session, written, later(600)), None);
+
+        session.paused = true;
+        assert_eq!(time_to_resume(&session, written, later(3600)), Some(600));
+        // Left paused for days
+        assert_eq!(time_to_resume(&session, written, later(PAUSED_RESUME_SECS + 1)), None);
+        assert_eq!(time_to_resume(&session, written, later(3 * 24 * 3600)), None);
+    }
+
+    #[test]
+    fn status_line_goes_idle_when_stale() {
+        let mut status = LiveStatus {
+            timer_type: TimerType::Work,
+            current_session: 2,
+            total_sessions: 4,
+            remaining_secs: 754,
+            overtime_secs: None,
+            paused: false,
+            updated: 1_000,
+        };
+        assert_eq!(format_status(Some(&status), 1_002), "🍅 12:34 WORK 2/4");
+        assert_eq!(format_status(Some(&status), 1_000 + STATUS_STALE_SECS + 1), "idle");
+        assert_eq!(format_status(None, 1_000), "idle");
+
+        status.overtime_secs = Some(65);
+        status.paused = true;
+        assert_eq!(format_status(Some(&status), 1_000), "🍅 +01:05 WORK 2/4 ⏸");
+    }
+}
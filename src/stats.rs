@@ -0,0 +1,296 @@
+use crate::history::HistoryEntry;
+use chrono::{Days, Local, NaiveDate, Weekday};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+
+// Focus score weights, see `focus_score`
+const COMPLETION_WEIGHT: f64 = 0.4;
+const RATING_WEIGHT: f64 = 0.3;
+const INTERRUPTION_WEIGHT: f64 = 0.3;
+
+// Completed work sessions, for the stats screen
+#[derive(Debug, Default, PartialEq)]
+pub struct Summary {
+    pub today: usize,
+    // Since Monday
+    pub this_week: usize,
+    pub all_time: usize,
+    pub focus_secs: u64,
+    // Consecutive days with a completed session, see `compute_streak`
+    pub streak: u32,
+}
+
+pub fn summarize(records: &[HistoryEntry], today: NaiveDate) -> Summary {
+    let monday = today.week(Weekday::Mon).first_day();
+    let mut summary = Summary::default();
+    let mut days = Vec::new();
+    for record in records.iter().filter(|r| !r.skipped) {
+        let day = record.timestamp.date_naive();
+        summary.today += usize::from(day == today);
+        summary.this_week += usize::from((monday..=today).contains(&day));
+        summary.all_time += 1;
+        summary.focus_secs += record.duration_secs;
+        days.push(day);
+    }
+    summary.streak = compute_streak(&days, today);
+    summary
+}
+
+// Days in a row with at least one completed session, counting back from
+// today, or from yesterday while today has none yet. Order and repeats in
+// `dates` don't matter
+pub fn compute_streak(dates: &[NaiveDate], today: NaiveDate) -> u32 {
+    let days: BTreeSet<NaiveDate> = dates.iter().copied().collect();
+    let mut day = if days.contains(&today) { today } else { today - Days::new(1) };
+    let mut streak = 0;
+    while days.contains(&day) {
+        streak += 1;
+        day = day - Days::new(1);
+    }
+    streak
+}
+
+pub fn stats_lines(records: &[HistoryEntry]) -> Vec<String> {
+    if records.is_empty() {
+        return vec!["No sessions yet".to_string()];
+    }
+
+    let count = records.len() as f64;
+    let skipped = records.iter().filter(|r| r.skipped).count();
+    let focus_secs: u64 = records
+        .iter()
+        .filter(|r| !r.skipped)
+        .map(|r| r.duration_secs)
+        .sum();
+    let pauses: u32 = records.iter().map(|r| r.pauses).sum();
+    let paused_secs: u64 = records.iter().map(|r| r.paused_secs).sum();
+    let interruptions: u32 = records.iter().map(|r| r.interruptions).sum();
+
+    let mut lines = vec![
+        format!("Sessions completed:     {}", records.len() - skipped),
+        format!("Sessions skipped:       {}", skipped),
+        format!("Focus time:             {}", format_duration(focus_secs)),
+        format!("Avg pauses/session:     {:.1}", pauses as f64 / count),
+        format!(
+            "Avg paused time:        {}",
+            format_duration((paused_secs as f64 / count).round() as u64)
+        ),
+        format!("Avg interruptions:      {:.1}", interruptions as f64 / count),
+    ];
+
+    // Days without sessions count as zero, so a quiet week shows up as a dip
+    let today = Local::now().date_naive();
+    lines.push(String::new());
+    lines.push("Daily average:".to_string());
+    for days in [7, 30] {
+        let (sessions, secs) = rolling_average(records, today, days);
+        lines.push(format!(
+            "  {:<22}{:.1} sessions, {}",
+            format!("Last {} days", days),
+            sessions,
+            format_duration(secs.round() as u64)
+        ));
+    }
+
+    // Most recent week with any sessions, newest first
+    let days = daily_focus_scores(records);
+    if !days.is_empty() {
+        lines.push(String::new());
+        lines.push("Focus score (0-100):".to_string());
+        for (day, score) in days.iter().rev().take(7) {
+            lines.push(format!("  {}             {}", day.format("%Y-%m-%d"), score));
+        }
+    }
+
+    let reasons = skip_reason_counts(records);
+    if !reasons.is_empty() {
+        lines.push(String::new());
+        lines.push("Breaks skipped because:".to_string());
+        for (reason, count) in reasons {
+            lines.push(format!("  {:<22}{}", reason, count));
+        }
+    }
+    lines
+}
+
+// Completed sessions and focus seconds per day over the `days` days ending
+// with `today`
+fn rolling_average(records: &[HistoryEntry], today: NaiveDate, days: u32) -> (f64, f64) {
+    let first = today - Days::new(days as u64 - 1);
+    let mut totals: BTreeMap<NaiveDate, (u32, u64)> = BTreeMap::new();
+    for record in records.iter().filter(|r| !r.skipped) {
+        let day = record.timestamp.date_naive();
+        if (first..=today).contains(&day) {
+            let total = totals.entry(day).or_default();
+            total.0 += 1;
+            total.1 += record.duration_secs;
+        }
+    }
+    let sessions: u32 = totals.values().map(|t| t.0).sum();
+    let secs: u64 = totals.values().map(|t| t.1).sum();
+    (sessions as f64 / days as f64, secs as f64 / days as f64)
+}
+
+// Most common first, ties alphabetically
+fn skip_reason_counts(records: &[HistoryEntry]) -> Vec<(&str, usize)> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for reason in records.iter().filter_map(|r| r.break_skip_reason.as_deref()) {
+        *counts.entry(reason).or_default() += 1;
+    }
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by_key(|&(_, count)| Reverse(count));
+    counts
+}
+
+pub fn daily_focus_scores(records: &[HistoryEntry]) -> Vec<(NaiveDate, u32)> {
+    let mut days: BTreeMap<NaiveDate, Vec<&HistoryEntry>> = BTreeMap::new();
+    for record in records {
+        days.entry(record.timestamp.date_naive()).or_default().push(record);
+    }
+    days.into_iter()
+        .filter_map(|(day, sessions)| focus_score(&sessions).map(|score| (day, score)))
+        .collect()
+}
+
+// Weighted average of three parts, each scaled to 0..1:
+//   completion    - share of sessions finished rather than skipped (40%)
+//   rating        - mean self-rating, 1 => 0.0 up to 5 => 1.0 (30%)
+//   interruptions - 1 / (1 + interruptions per session) (30%)
+// Unrated sessions are left out of the rating part; with no ratings at all
+// that part is dropped and the other two weights are scaled up to match
+pub fn focus_score(sessions: &[&HistoryEntry]) -> Option<u32> {
+    if sessions.is_empty() {
+        return None;
+    }
+    let count = sessions.len() as f64;
+
+    let completed = sessions.iter().filter(|s| !s.skipped).count() as f64;
+    let interruptions: u32 = sessions.iter().map(|s| s.interruptions).sum();
+    let mut parts = vec![
+        (completed / count, COMPLETION_WEIGHT),
+        (1.0 / (1.0 + interruptions as f64 / count), INTERRUPTION_WEIGHT),
+    ];
+
+    let ratings: Vec<f64> = sessions
+        .iter()
+        .filter_map(|s| s.rating)
+        .map(|r| (r.clamp(1, 5) - 1) as f64 / 4.0)
+        .collect();
+    if !ratings.is_empty() {
+        parts.push((ratings.iter().sum::<f64>() / ratings.len() as f64, RATING_WEIGHT));
+    }
+
+    let weight: f64 = parts.iter().map(|(_, w)| w).sum();
+    let score: f64 = parts.iter().map(|(value, w)| value * w).sum::<f64>() / weight;
+    Some((score * 100.0).round() as u32)
+}
+
+pub fn format_duration(secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, secs % 3600 / 60, secs % 60);
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if seconds > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Local;
+
+    fn session(skipped: bool, interruptions: u32, rating: Option<u8>) -> HistoryEntry {
+        HistoryEntry { interruptions, rating, skipped, ..HistoryEntry::at(Local::now()) }
+    }
+
+    #[test]
+    fn perfect_day_scores_100() {
+        let a = session(false, 0, Some(5));
+        let b = session(false, 0, None);
+        assert_eq!(focus_score(&[&a, &b]), Some(100));
+    }
+
+    #[test]
+    fn unrated_day_ignores_rating_part() {
+        // completion 0.5, interruptions 1 / (1 + 1) = 0.5
+        let a = session(false, 2, None);
+        let b = session(true, 0, None);
+        assert_eq!(focus_score(&[&a, &b]), Some(50));
+    }
+
+    #[test]
+    fn rating_counts_only_rated_sessions() {
+        // completion 1.0, interruptions 1.0, rating (3 - 1) / 4 = 0.5
+        let a = session(false, 0, Some(3));
+        let b = session(false, 0, None);
+        assert_eq!(focus_score(&[&a, &b]), Some(85));
+    }
+
+    #[test]
+    fn skip_reasons_by_frequency() {
+        let with_reason = |reason: Option<&str>| HistoryEntry {
+            break_skip_reason: reason.map(String::from),
+            ..session(false, 0, None)
+        };
+        let records = [
+            with_reason(Some("meeting")),
+            with_reason(Some("in flow")),
+            with_reason(None),
+            with_reason(Some("in flow")),
+        ];
+        assert_eq!(skip_reason_counts(&records), [("in flow", 2), ("meeting", 1)]);
+    }
+
+    #[test]
+    fn rolling_average_counts_empty_days() {
+        let today = Local::now().date_naive();
+        let days_ago = |days: i64, skipped: bool| HistoryEntry {
+            timestamp: Local::now() - chrono::Duration::days(days),
+            ..session(skipped, 0, None)
+        };
+        let records = [days_ago(0, false), days_ago(0, false), days_ago(3, false), days_ago(3, true), days_ago(10, false)];
+        // 3 sessions of 1500s over 7 days
+        assert_eq!(rolling_average(&records, today, 7), (3.0 / 7.0, 4500.0 / 7.0));
+        assert_eq!(rolling_average(&records, today, 30), (4.0 / 30.0, 6000.0 / 30.0));
+    }
+
+    #[test]
+    fn summary_buckets_by_day_and_week() {
+        let at = |date: &str, skipped: bool| HistoryEntry {
+            timestamp: NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+                .and_local_timezone(Local)
+                .unwrap(),
+            ..session(skipped, 0, None)
+        };
+        // 2025-01-15 is a Wednesday
+        let records = [at("2025-01-15", false), at("2025-01-15", true), at("2025-01-13", false), at("2025-01-12", false)];
+        let today = NaiveDate::from_ymd_opt(2025, 1, 15).unwrap();
+        assert_eq!(
+            summarize(&records, today),
+            Summary { today: 1, this_week: 2, all_time: 3, focus_secs: 4500, streak: 1 }
+        );
+    }
+
+    #[test]
+    fn streak_counts_consecutive_days() {
+        let day = |d: u32| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        // Today included, repeats and order ignored
+        assert_eq!(compute_streak(&[day(10), day(8), day(9), day(10)], day(10)), 3);
+        // Nothing yet today: the streak up to yesterday still stands
+        assert_eq!(compute_streak(&[day(8), day(9)], day(10)), 2);
+        // A missed day breaks it
+        assert_eq!(compute_streak(&[day(5), day(6), day(8)], day(10)), 0);
+        assert_eq!(compute_streak(&[day(5), day(6), day(8), day(9), day(10)], day(10)), 3);
+        assert_eq!(compute_streak(&[], day(10)), 0);
+    }
+
+    #[test]
+    fn no_sessions_no_score() {
+        assert_eq!(focus_score(&[]), None);
+    }
+}
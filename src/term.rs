@@ -0,0 +1,77 @@
+use crossterm::{cursor, execute, terminal};
+use std::io;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+
+// Set while the terminal is taken over, so it is given back exactly once:
+// by the panic hook before the panic message prints, or by the guard's drop
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static ALTERNATE: AtomicBool = AtomicBool::new(false);
+
+// Raw mode, and the alternate screen with the cursor hidden, for as long as
+// this is held. Dropping it restores the terminal, on an early return or a
+// panic as much as on a normal exit
+pub struct TerminalGuard;
+
+impl TerminalGuard {
+    // Screen-reader mode prints plain lines and keeps the main screen
+    pub fn enter(alternate: bool) -> io::Result<Self> {
+        install_hook();
+        terminal::enable_raw_mode()?;
+        ACTIVE.store(true, Ordering::SeqCst);
+        let guard = TerminalGuard;
+        if alternate {
+            ALTERNATE.store(true, Ordering::SeqCst);
+            execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        }
+        Ok(guard)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+// Best effort: there is nowhere left to report a failure
+fn restore() {
+    if !ACTIVE.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    if ALTERNATE.swap(false, Ordering::SeqCst) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+    }
+    let _ = terminal::disable_raw_mode();
+}
+
+// Runs ahead of the default hook, which then prints the message on a
+// terminal that can show it
+fn install_hook() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            restore();
+            default(info);
+        }));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guard_restores_when_its_scope_panics() {
+        // Taken over without touching the test runner's terminal
+        ACTIVE.store(true, Ordering::SeqCst);
+        let result = panic::catch_unwind(|| {
+            let _guard = TerminalGuard;
+            panic!("draw failed");
+        });
+        assert!(result.is_err());
+        assert!(!ACTIVE.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,219 @@
+use crate::log;
+use std::collections::HashMap;
+
+// Terminal colors, converted to crossterm's by the TUI
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Grey,
+    DarkGrey,
+    DarkRed,
+    DarkGreen,
+    DarkYellow,
+    DarkBlue,
+    DarkMagenta,
+    DarkCyan,
+    Rgb { r: u8, g: u8, b: u8 },
+}
+
+#[cfg(feature = "tui")]
+impl From<Color> for crossterm::style::Color {
+    fn from(color: Color) -> Self {
+        use crossterm::style::Color as Term;
+        match color {
+            Color::Black => Term::Black,
+            Color::Red => Term::Red,
+            Color::Green => Term::Green,
+            Color::Yellow => Term::Yellow,
+            Color::Blue => Term::Blue,
+            Color::Magenta => Term::Magenta,
+            Color::Cyan => Term::Cyan,
+            Color::White => Term::White,
+            Color::Grey => Term::Grey,
+            Color::DarkGrey => Term::DarkGrey,
+            Color::DarkRed => Term::DarkRed,
+            Color::DarkGreen => Term::DarkGreen,
+            Color::DarkYellow => Term::DarkYellow,
+            Color::DarkBlue => Term::DarkBlue,
+            Color::DarkMagenta => Term::DarkMagenta,
+            Color::DarkCyan => Term::DarkCyan,
+            Color::Rgb { r, g, b } => Term::Rgb { r, g, b },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Theme {
+    pub name: &'static str,
+    pub work: Color,
+    pub short_break: Color,
+    pub long_break: Color,
+    // Screen titles and focused input borders
+    pub title: Color,
+    pub text: Color,
+    // Labels, status and help lines
+    pub help: Color,
+    // Setup errors and the overtime count
+    pub alert: Color,
+}
+
+pub const THEMES: &[Theme] = &[
+    Theme {
+        name: "default",
+        work: Color::Cyan,
+        short_break: Color::Yellow,
+        long_break: Color::Green,
+        title: Color::Cyan,
+        text: Color::White,
+        help: Color::DarkGrey,
+        alert: Color::Red,
+    },
+    Theme {
+        name: "solarized",
+        work: Color::Rgb { r: 38, g: 139, b: 210 },
+        short_break: Color::Rgb { r: 181, g: 137, b: 0 },
+        long_break: Color::Rgb { r: 133, g: 153, b: 0 },
+        title: Color::Rgb { r: 42, g: 161, b: 152 },
+        text: Color::Rgb { r: 147, g: 161, b: 161 },
+        help: Color::Rgb { r: 88, g: 110, b: 117 },
+        alert: Color::Rgb { r: 220, g: 50, b: 47 },
+    },
+    Theme {
+        name: "mono",
+        work: Color::White,
+        short_break: Color::Grey,
+        long_break: Color::Grey,
+        title: Color::White,
+        text: Color::White,
+        help: Color::DarkGrey,
+        // Grayscale only, for e-ink and colorblind use
+        alert: Color::White,
+    },
+];
+
+// Position in THEMES, falling back to "default" for unknown names
+pub fn theme_index(name: &str) -> usize {
+    THEMES.iter().position(|theme| theme.name == name).unwrap_or(0)
+}
+
+// The named theme with the config's `[colors]` on top
+pub fn load(name: &str, colors: &HashMap<String, String>) -> Theme {
+    THEMES[theme_index(name)].with_colors(colors)
+}
+
+impl Theme {
+    // Entries that don't parse, or name no part of the theme, keep the
+    // theme's own color and are logged
+    pub fn with_colors(mut self, colors: &HashMap<String, String>) -> Theme {
+        for (part, text) in colors {
+            let slot = match part.as_str() {
+                "work" => &mut self.work,
+                "short_break" => &mut self.short_break,
+                "long_break" => &mut self.long_break,
+                "title" => &mut self.title,
+                "text" => &mut self.text,
+                "help" => &mut self.help,
+                "alert" => &mut self.alert,
+                _ => {
+                    log::debug(&format!("colors: unknown entry '{}'", part));
+                    continue;
+                }
+            };
+            match parse_color(text) {
+                Some(color) => *slot = color,
+                None => log::debug(&format!("colors: invalid color '{}' for {}", text, part)),
+            }
+        }
+        self
+    }
+}
+
+// "#ff8800", "#f80" or a terminal color name such as "cyan" or "dark_grey"
+pub fn parse_color(text: &str) -> Option<Color> {
+    let text = text.trim();
+    if let Some(hex) = text.strip_prefix('#') {
+        let digits: Vec<u8> = hex
+            .chars()
+            .map(|c| c.to_digit(16).map(|d| d as u8))
+            .collect::<Option<_>>()?;
+        let [r, g, b] = match digits[..] {
+            [r, g, b] => [r * 17, g * 17, b * 17],
+            [r1, r2, g1, g2, b1, b2] => [r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2],
+            _ => return None,
+        };
+        return Some(Color::Rgb { r, g, b });
+    }
+    let name: String = text
+        .chars()
+        .filter(|c| !matches!(c, '_' | '-' | ' '))
+        .collect::<String>()
+        .to_ascii_lowercase()
+        .replace("gray", "grey");
+    Some(match name.as_str() {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "white" => Color::White,
+        "grey" => Color::Grey,
+        "darkgrey" => Color::DarkGrey,
+        "darkred" => Color::DarkRed,
+        "darkgreen" => Color::DarkGreen,
+        "darkyellow" => Color::DarkYellow,
+        "darkblue" => Color::DarkBlue,
+        "darkmagenta" => Color::DarkMagenta,
+        "darkcyan" => Color::DarkCyan,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn themes_are_found_by_name_with_default_as_fallback() {
+        assert_eq!(THEMES[theme_index("solarized")].name, "solarized");
+        assert_eq!(THEMES[theme_index("mono")].name, "mono");
+        assert_eq!(THEMES[theme_index("neon")].name, "default");
+    }
+
+    #[test]
+    fn colors_parse_from_hex_and_names() {
+        assert_eq!(parse_color("#fff"), Some(Color::Rgb { r: 255, g: 255, b: 255 }));
+        assert_eq!(parse_color("#ff8800"), Some(Color::Rgb { r: 255, g: 136, b: 0 }));
+        assert_eq!(parse_color("cyan"), Some(Color::Cyan));
+        assert_eq!(parse_color("Dark_Gray"), Some(Color::DarkGrey));
+        assert_eq!(parse_color("nonsense"), None);
+        assert_eq!(parse_color("#ff88"), None);
+        assert_eq!(parse_color("#ggg"), None);
+    }
+
+    #[test]
+    fn bad_colors_keep_the_theme_default() {
+        let colors = HashMap::from([
+            ("work".to_string(), "#ff8800".to_string()),
+            ("help".to_string(), "nonsense".to_string()),
+        ]);
+        let theme = load("default", &colors);
+        assert_eq!(theme.work, Color::Rgb { r: 255, g: 136, b: 0 });
+        assert_eq!(theme.help, THEMES[0].help);
+    }
+
+    #[test]
+    fn mono_is_grayscale() {
+        let mono = THEMES[theme_index("mono")];
+        let colors = [mono.work, mono.short_break, mono.long_break, mono.title, mono.text, mono.help, mono.alert];
+        assert!(colors.iter().all(|c| matches!(c, Color::White | Color::Grey | Color::DarkGrey)));
+    }
+}
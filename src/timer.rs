@@ -0,0 +1,834 @@
+use crate::history::HistoryEntry;
+use crate::{PomodoroApp, MAX_TIME_LEFT};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppState {
+    Setup,
+    Running,
+    BreakPrompt,
+    // Break over, waiting to be sent back to work (auto_start_work off)
+    WorkPrompt,
+    // Short "Break starting..." count between the prompt and the break
+    BreakLeadIn,
+    // Pre-work checklist shown one step at a time before the first session
+    Ritual,
+    // Completed-session totals, opened from setup
+    Stats,
+    // Work time is up but the session goes on, counting up (overtime on)
+    Overtime,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerType {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+// What the state machine wants done in the outside world. The timer logic
+// below only changes state and returns these; the caller carries them out
+#[derive(Clone, Debug, PartialEq)]
+pub enum Effect {
+    // Desktop notification
+    Notify(String),
+    // Completion sound and vibration
+    PlaySound,
+    // Countdown beep for the second just reached
+    Tick,
+    // A finished or skipped work session for the history
+    Log(HistoryEntry),
+    // Reason picked for the break just skipped, for the last history record
+    SkipReason(String),
+    // The last session is over
+    Exit,
+}
+
+// The run's state machine. Nothing here touches the terminal, sound or
+// files, so it can be driven directly in tests
+impl PomodoroApp {
+    pub fn begin(&mut self, timer_type: TimerType) {
+        self.timer_type = timer_type;
+        self.time_left = self.duration_for(timer_type);
+        self.session_total = self.time_left;
+        self.paused = false;
+        self.state = AppState::Running;
+        self.reset_session_tracking();
+    }
+    
+    pub fn reset_session_tracking(&mut self) {
+        self.idle_paused = false;
+        self.pause_count = 0;
+        self.paused_total = Duration::ZERO;
+        self.paused_at = None;
+        self.interruptions = 0;
+        self.session_end = None;
+        self.overtime_since = None;
+        self.overtime_done = Duration::ZERO;
+    }
+    
+    pub fn toggle_pause(&mut self) {
+        self.toggle_pause_at(Instant::now());
+    }
+    
+    // Pausing keeps the exact time left; resuming counts down from it again.
+    // A pause landing after the time ran out is dropped, so the next sync
+    // ends the timer as usual instead of freezing it at 0:00
+    pub fn toggle_pause_at(&mut self, now: Instant) {
+        if !self.paused && self.time_up(now) {
+            return;
+        }
+        self.paused = !self.paused;
+        if self.paused {
+            self.pause_count += 1;
+            self.paused_at = Some(now);
+            self.stop_clock(now);
+        } else {
+            if let Some(start) = self.paused_at.take() {
+                self.paused_total += now.saturating_duration_since(start);
+            }
+            self.session_end = Some(now + self.time_left);
+        }
+    }
+    
+    // Starts the current timer over at its configured length, dropping any
+    // +1m/-1m or edited time, and keeping the session number
+    pub fn restart_timer(&mut self) {
+        self.time_left = match self.timer_type {
+            // Already counted as taken when it started
+            TimerType::LongBreak => self.long_break_for(self.long_breaks_taken.saturating_sub(1)),
+            timer_type => self.duration_for(timer_type),
+        };
+        self.session_total = self.time_left;
+        self.paused = false;
+        self.reset_session_tracking();
+    }
+    
+    // Adds up to a minute, never past MAX_TIME_LEFT. The session grows
+    // with it, so the progress bar keeps its place
+    pub fn add_minute(&mut self) {
+        let added = MAX_TIME_LEFT.saturating_sub(self.time_left).min(Duration::from_secs(60));
+        if added.is_zero() {
+            self.command_message = Some(format!("Maximum reached ({} minutes)", MAX_TIME_LEFT.as_secs() / 60));
+            return;
+        }
+        self.time_left += added;
+        self.session_total += added;
+        self.session_end = self.session_end.map(|end| end + added);
+    }
+    
+    // Takes a minute off unless that would go below the timer type's
+    // floor, or a minute, whichever is more
+    pub fn sub_minute(&mut self) {
+        let floor = Duration::from_secs(match self.timer_type {
+            TimerType::Work => self.min_remaining.work,
+            TimerType::ShortBreak => self.min_remaining.short_break,
+            TimerType::LongBreak => self.min_remaining.long_break,
+        }.max(60));
+        match self.time_left.checked_sub(Duration::from_secs(60)) {
+            Some(left) if left >= floor => {
+                self.time_left = left;
+                self.session_total = self.session_total.saturating_sub(Duration::from_secs(60));
+                self.session_end = self.session_end.map(|end| end - Duration::from_secs(60));
+            }
+            _ => {
+                let secs = floor.as_secs();
+                self.command_message = Some(format!("Minimum reached ({}:{:02} left at least)", secs / 60, secs % 60));
+            }
+        }
+    }
+    
+    pub fn work_entry(&mut self) -> HistoryEntry {
+        HistoryEntry {
+            timestamp: self.clock.now(),
+            duration_secs: self.session_total.as_secs(),
+            session: self.current_session,
+            task: self.current_task.as_ref().map(|task| task.text.clone()),
+            label: Some(self.task_label.clone()).filter(|label| !label.is_empty()),
+            pauses: self.pause_count,
+            paused_secs: self.total_paused().as_secs(),
+            interruptions: self.interruptions,
+            tag: None,
+            rating: None,
+            skipped: !self.time_left.is_zero(),
+            counted: self.work_counts(),
+            break_skip_reason: None,
+            overtime_secs: self.overtime_done.as_secs(),
+        }
+    }
+    
+    // Whether the work session ending now uses up one of `total_sessions`;
+    // only skipped ones can be left out, see `count_skipped_work`
+    pub fn work_counts(&self) -> bool {
+        self.time_left.is_zero() || self.count_skipped_work
+    }
+    
+    // A long break every `long_break_interval` sessions; 0 means never
+    pub fn break_type_after(&self, session: u32) -> TimerType {
+        if self.long_break_interval > 0 && session.is_multiple_of(self.long_break_interval) {
+            TimerType::LongBreak
+        } else {
+            TimerType::ShortBreak
+        }
+    }
+    
+    // The break after the current work session, given whether it ran out.
+    // With long_break_needs_completed the long break waits until
+    // `long_break_interval` sessions have run out since the last one, so
+    // skipped sessions are followed by short breaks
+    pub fn break_after_work(&self, completed: bool) -> TimerType {
+        if !self.long_break_needs_completed {
+            return self.break_type_after(self.current_session);
+        }
+        let earned = self.work_since_long_break + completed as u32;
+        if self.long_break_interval > 0 && earned >= self.long_break_interval {
+            TimerType::LongBreak
+        } else {
+            TimerType::ShortBreak
+        }
+    }
+    
+    // Long breaks are the next one the cycle has in store
+    pub fn duration_for(&self, timer_type: TimerType) -> Duration {
+        match timer_type {
+            TimerType::Work => self.work_duration,
+            TimerType::ShortBreak => self.short_break_duration,
+            TimerType::LongBreak => self.long_break_for(self.long_breaks_taken),
+        }
+    }
+    
+    // Length of the nth long break (0-based); past the end of the cycle the
+    // last value repeats, without a cycle it is the plain long break
+    pub fn long_break_for(&self, nth: usize) -> Duration {
+        match self.long_break_cycle.last() {
+            Some(last) => Duration::from_secs(*self.long_break_cycle.get(nth).unwrap_or(last) as u64 * 60),
+            None => self.long_break_duration,
+        }
+    }
+    
+    // Planned time for every phase after the current one, at the
+    // durations currently in effect
+    pub fn planned_after_current(&self) -> Duration {
+        let mut long_breaks = self.long_breaks_taken;
+        let mut break_length = |session: u32| match self.break_type_after(session) {
+            TimerType::LongBreak => {
+                long_breaks += 1;
+                self.long_break_for(long_breaks - 1)
+            }
+            break_type => self.duration_for(break_type),
+        };
+        
+        let mut planned = Duration::ZERO;
+        if self.timer_type == TimerType::Work && self.current_session < self.total_sessions {
+            planned += break_length(self.current_session);
+        }
+        for session in self.current_session + 1..=self.total_sessions {
+            planned += self.work_duration;
+            if session < self.total_sessions {
+                planned += break_length(session);
+            }
+        }
+        planned
+    }
+    
+    // How far the current countdown is, 0.0 to 1.0
+    pub fn timer_progress(&self) -> f64 {
+        if self.session_total.is_zero() {
+            return 0.0;
+        }
+        let left = self.time_left.min(self.session_total);
+        1.0 - left.as_secs_f64() / self.session_total.as_secs_f64()
+    }
+    
+    // Fraction of the whole run (all sessions and breaks) behind us
+    pub fn run_progress(&self) -> f64 {
+        let total = self.run_elapsed + self.time_left + self.planned_after_current();
+        if total.is_zero() {
+            return 0.0;
+        }
+        self.run_elapsed.as_secs_f64() / total.as_secs_f64()
+    }
+    
+    // Whole seconds shown, rounded up so a fresh 25:00 timer reads 25:00
+    // and 0:00 only shows once time is really up
+    pub fn secs_left(&self) -> u64 {
+        self.time_left.as_secs() + u64::from(self.time_left.subsec_nanos() > 0)
+    }
+    
+    pub fn counting(&self) -> bool {
+        self.state == AppState::Running && !self.paused && !self.deferred() && !self.timer_held()
+    }
+    
+    // Called every frame. The time left is worked out from the end instant
+    // rather than counted down in steps, so slow frames never add up to
+    // drift
+    pub fn sync_clock(&mut self, now: Instant) -> Vec<Effect> {
+        if !self.counting() {
+            self.stop_clock(now);
+            return Vec::new();
+        }
+        let end = *self.session_end.get_or_insert(now + self.time_left);
+        self.count_down_to(end.saturating_duration_since(now))
+    }
+    
+    // Ran out, whether or not a frame has noticed yet
+    pub fn time_up(&self, now: Instant) -> bool {
+        match self.session_end {
+            Some(end) => end <= now,
+            None => self.time_left.is_zero(),
+        }
+    }
+    
+    // Freezes the time left as of `now`
+    pub fn stop_clock(&mut self, now: Instant) {
+        if let Some(end) = self.session_end.take() {
+            let left = end.saturating_duration_since(now);
+            self.run_elapsed += self.time_left.saturating_sub(left);
+            self.time_left = left;
+        }
+    }
+    
+    // One second of countdown, for the self-test
+    pub fn tick(&mut self) -> Vec<Effect> {
+        self.count_down_to(self.time_left.saturating_sub(Duration::from_secs(1)))
+    }
+    
+    pub fn count_down_to(&mut self, left: Duration) -> Vec<Effect> {
+        let shown = self.secs_left();
+        self.run_elapsed += self.time_left.saturating_sub(left);
+        self.time_left = left;
+        let mut effects = Vec::new();
+        if self.secs_left() != shown {
+            effects.push(Effect::Tick);
+        }
+        
+        if self.time_left.is_zero() {
+            let ended_at = self.session_end.take().unwrap_or_else(Instant::now);
+            if self.overtime && self.timer_type == TimerType::Work {
+                self.state = AppState::Overtime;
+                self.overtime_since = Some(ended_at);
+                effects.extend([notify("Work session finished! Keep going, or take your break when ready."), Effect::PlaySound]);
+            } else {
+                effects.extend(self.advance_timer());
+            }
+        }
+        effects
+    }
+    
+    // Time worked past the bell so far
+    pub fn overtime_at(&self, now: Instant) -> Duration {
+        self.overtime_since.map_or(self.overtime_done, |since| now.saturating_duration_since(since))
+    }
+    
+    // Stops the overtime count as of `now`; the session then ends as usual
+    // with advance_timer or skip_to_work
+    pub fn end_overtime(&mut self, now: Instant) {
+        self.overtime_done = self.overtime_at(now);
+        self.overtime_since = None;
+    }
+    
+    // Ends the current timer, whether it ran out or was skipped
+    pub fn advance_timer(&mut self) -> Vec<Effect> {
+        match self.timer_type {
+            TimerType::Work => {
+                let counts = self.work_counts();
+                let break_type = self.break_after_work(self.time_left.is_zero());
+                let mut effects = vec![Effect::Log(self.work_entry())];
+                if self.time_left.is_zero() {
+                    self.completed_work_sessions += 1;
+                    self.work_since_long_break += 1;
+                    self.focus_time += self.session_total + self.overtime_done;
+                }
+                // Announced when overtime began
+                let announced = self.state == AppState::Overtime;
+                
+                // An uncounted skip gives the session back: same number,
+                // no break, fresh countdown
+                if !counts {
+                    self.begin(TimerType::Work);
+                    self.command_message = Some(format!("Skipped, not counted: session {} restarted", self.current_session));
+                    return effects;
+                }
+                
+                // Work session finished - show break prompt
+                if self.current_session < self.total_sessions {
+                    if break_type == TimerType::LongBreak {
+                        self.next_break_type = Some(TimerType::LongBreak);
+                        effects.push(notify("Work session finished! Time for a long break."));
+                    } else {
+                        self.next_break_type = Some(TimerType::ShortBreak);
+                        effects.push(notify("Work session finished! Time for a short break."));
+                    }
+                    self.state = AppState::BreakPrompt;
+                    effects.push(Effect::PlaySound);
+                    if announced {
+                        effects.retain(|effect| matches!(effect, Effect::Log(_)));
+                    }
+                    if self.auto_start_breaks {
+                        self.confirm_break();
+                    }
+                } else if self.once {
+                    effects.extend([notify("Focus session complete! 🎉"), Effect::PlaySound, Effect::Exit]);
+                } else {
+                    effects.extend([notify(&self.run_summary()), Effect::Exit]);
+                }
+                effects
+            }
+            TimerType::ShortBreak | TimerType::LongBreak => {
+                // Break finished - go back to work
+                let msg = if self.timer_type == TimerType::ShortBreak {
+                    "Short break finished! Back to work."
+                } else {
+                    "Long break finished! Back to work."
+                };
+                let mut effects = vec![notify(msg)];
+                
+                self.current_session += 1;
+                if self.current_session > self.total_sessions {
+                    effects.extend([notify(&self.run_summary()), Effect::Exit]);
+                } else {
+                    if self.auto_start_work {
+                        self.begin(TimerType::Work);
+                    } else {
+                        self.state = AppState::WorkPrompt;
+                    }
+                    effects.push(Effect::PlaySound);
+                }
+                effects
+            }
+        }
+    }
+    
+    // A skip landing after the time ran out but before a frame noticed ends
+    // the timer the normal way, so it isn't logged as skipped or ended twice
+    pub fn skip_at(&mut self, now: Instant) -> Vec<Effect> {
+        if self.counting() && self.time_up(now) {
+            return self.sync_clock(now);
+        }
+        self.advance_timer()
+    }
+    
+    // Past the rest of this timer and any break after it, into the next
+    // work session with no prompt in between. Only the history entry is
+    // kept from what the skipped phases would have announced
+    pub fn skip_to_work(&mut self) -> Vec<Effect> {
+        let mut effects = self.advance_timer();
+        if effects.contains(&Effect::Exit) {
+            return effects;
+        }
+        effects.retain(|effect| matches!(effect, Effect::Log(_)));
+        match self.state {
+            AppState::BreakPrompt | AppState::BreakLeadIn => effects.extend(self.skip_break()),
+            AppState::Running if self.timer_type != TimerType::Work => effects.extend(self.skip_break()),
+            AppState::WorkPrompt => self.begin(TimerType::Work),
+            _ => {}
+        }
+        effects
+    }
+    
+    // Confirmed at the prompt: straight into the break, or via the lead-in
+    pub fn confirm_break(&mut self) {
+        if self.break_leadin.is_zero() {
+            self.start_break();
+        } else {
+            self.state = AppState::BreakLeadIn;
+            self.leadin_until = Some(Instant::now() + self.break_leadin);
+        }
+    }
+    
+    pub fn check_leadin(&mut self) {
+        if self.leadin_until.is_some_and(|until| Instant::now() >= until) {
+            self.start_break();
+        }
+    }
+    
+    // Straight on to the next work session
+    pub fn skip_break(&mut self) -> Vec<Effect> {
+        self.leadin_until = None;
+        self.current_session += 1;
+        if self.current_session > self.total_sessions {
+            return vec![notify(&self.run_summary()), Effect::Exit];
+        }
+        self.begin(TimerType::Work);
+        self.next_break_type = None;
+        Vec::new()
+    }
+    
+    // The one notification at the end of the run
+    pub fn run_summary(&self) -> String {
+        let count = self.completed_work_sessions;
+        format!(
+            "All sessions completed! 🎉 Completed {} pomodoro{} — {} focus minutes",
+            count,
+            if count == 1 { "" } else { "s" },
+            self.focus_time.as_secs() / 60
+        )
+    }
+    
+    pub fn start_break(&mut self) {
+        self.leadin_until = None;
+        if let Some(break_type) = self.next_break_type {
+            self.timer_type = break_type;
+            let duration = self.duration_for(break_type);
+            if break_type == TimerType::LongBreak {
+                self.long_breaks_taken += 1;
+                self.work_since_long_break = 0;
+            }
+            self.time_left = duration;
+            self.session_total = self.time_left;
+            self.paused = false;
+            self.reset_session_tracking();
+            self.state = AppState::Running;
+            self.next_break_type = None;
+        }
+    }
+}
+
+fn notify(message: &str) -> Effect {
+    Effect::Notify(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{self, Config};
+
+    #[test]
+    fn full_run_through_the_state_machine() {
+        let mut app = PomodoroApp::new(&Config { total_sessions: 2, ..Default::default() });
+        app.start_timer();
+        assert_eq!((app.state, app.timer_type, app.current_session), (AppState::Running, TimerType::Work, 1));
+        
+        // The first work session runs out
+        app.time_left = Duration::from_secs(1);
+        let effects = app.tick();
+        assert!(matches!(
+            &effects[..],
+            [Effect::Tick, Effect::Log(entry), Effect::Notify(_), Effect::PlaySound] if entry.session == 1 && !entry.skipped
+        ));
+        assert_eq!(app.state, AppState::BreakPrompt);
+        assert_eq!(app.next_break_type, Some(TimerType::ShortBreak));
+        
+        app.confirm_break();
+        assert_eq!((app.state, app.timer_type), (AppState::Running, TimerType::ShortBreak));
+        assert_eq!(app.time_left, Duration::from_secs(5 * 60));
+        
+        // Skipping the break starts work session 2
+        let effects = app.advance_timer();
+        assert_eq!(effects, [notify("Short break finished! Back to work."), Effect::PlaySound]);
+        assert_eq!((app.state, app.timer_type, app.current_session), (AppState::Running, TimerType::Work, 2));
+        
+        // Skipping the last one ends the run
+        let effects = app.advance_timer();
+        assert!(matches!(&effects[0], Effect::Log(entry) if entry.session == 2 && entry.skipped));
+        assert_eq!(effects.last(), Some(&Effect::Exit));
+    }
+
+    #[test]
+    fn auto_start_toggles_are_independent() {
+        for auto_start_breaks in [false, true] {
+            for auto_start_work in [false, true] {
+                let config = Config { auto_start_breaks, auto_start_work, ..Default::default() };
+                let mut app = PomodoroApp::new(&config);
+                app.start_timer();
+                
+                assert!(!app.advance_timer().contains(&Effect::Exit));
+                let after_work = if auto_start_breaks { AppState::Running } else { AppState::BreakPrompt };
+                assert_eq!(app.state, after_work);
+                if !auto_start_breaks {
+                    app.confirm_break();
+                }
+                assert_eq!(app.timer_type, TimerType::ShortBreak);
+                
+                assert!(!app.advance_timer().contains(&Effect::Exit));
+                let after_break = if auto_start_work { AppState::Running } else { AppState::WorkPrompt };
+                assert_eq!(app.state, after_break);
+                assert_eq!(app.current_session, 2);
+            }
+        }
+    }
+
+    #[test]
+    fn restart_uses_the_configured_length() {
+        let mut app = PomodoroApp::new(&Config { work_minutes: 50, ..Default::default() });
+        app.start_timer();
+        app.add_minute();
+        app.add_minute();
+        app.time_left = Duration::from_secs(100);
+        app.toggle_pause();
+        app.restart_timer();
+        assert_eq!(app.time_left, Duration::from_secs(50 * 60));
+        assert_eq!(app.session_total, Duration::from_secs(50 * 60));
+        assert!(!app.paused);
+        assert_eq!(app.current_session, 1);
+        
+        // The first step of a long break cycle, not the next one
+        let mut app = PomodoroApp::new(&Config { long_break_cycle: vec![15, 20], ..Default::default() });
+        app.next_break_type = Some(TimerType::LongBreak);
+        app.start_break();
+        app.add_minute();
+        app.restart_timer();
+        assert_eq!(app.time_left, Duration::from_secs(15 * 60));
+    }
+
+    #[test]
+    fn pause_keeps_exact_time_left() {
+        let mut app = PomodoroApp::new(&Config::default());
+        app.start_timer();
+        let start = Instant::now();
+        let at = |secs: f64| start + Duration::from_secs_f64(secs);
+        
+        app.sync_clock(start);
+        app.sync_clock(at(10.5));
+        assert_eq!(app.time_left, Duration::from_secs_f64(1489.5));
+        assert_eq!(app.secs_left(), 1490);
+        
+        app.toggle_pause_at(at(10.75));
+        app.sync_clock(at(70.0));
+        assert_eq!(app.time_left, Duration::from_secs_f64(1489.25));
+        
+        app.toggle_pause_at(at(70.0));
+        assert_eq!(app.paused_total, Duration::from_secs_f64(59.25));
+        app.sync_clock(at(80.0));
+        assert_eq!(app.time_left, Duration::from_secs_f64(1479.25));
+        assert_eq!(app.run_elapsed, Duration::from_secs_f64(20.75));
+    }
+
+    #[test]
+    fn pauses_add_up_and_survive_a_skip() {
+        let mut app = PomodoroApp::new(&Config::default());
+        app.start_timer();
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        
+        app.sync_clock(start);
+        app.toggle_pause_at(at(10));
+        app.toggle_pause_at(at(40));
+        app.toggle_pause_at(at(100));
+        app.toggle_pause_at(at(160));
+        assert_eq!(app.paused_total, Duration::from_secs(90));
+        
+        let effects = app.skip_at(at(170));
+        let entry = effects.iter().find_map(|effect| match effect {
+            Effect::Log(entry) => Some(entry),
+            _ => None,
+        });
+        assert_eq!(entry.map(|e| (e.pauses, e.paused_secs)), Some((2, 90)));
+        // The break starts from nothing
+        app.confirm_break();
+        assert_eq!(app.paused_total, Duration::ZERO);
+    }
+
+    #[test]
+    fn long_break_cycle_repeats_last_value() {
+        let config = Config { long_break_cycle: vec![15, 20, 30], ..Config::default() };
+        let app = PomodoroApp::new(&config);
+        let minutes: Vec<u64> = (0..5).map(|nth| app.long_break_for(nth).as_secs() / 60).collect();
+        assert_eq!(minutes, [15, 20, 30, 30, 30]);
+    }
+
+    #[test]
+    fn skipped_work_counts_by_default() {
+        let mut app = PomodoroApp::new(&Config::default());
+        app.time_left = Duration::from_secs(300);
+        assert!(app.work_counts());
+        let entry = app.work_entry();
+        assert!(entry.skipped && entry.counted);
+    }
+
+    #[test]
+    fn skipped_work_can_be_left_uncounted() {
+        let config = Config { count_skipped_work: false, ..Config::default() };
+        let mut app = PomodoroApp::new(&config);
+        app.time_left = Duration::from_secs(300);
+        assert!(!app.work_counts());
+        let entry = app.work_entry();
+        assert!(entry.skipped && !entry.counted);
+
+        // Running out normally always counts
+        app.time_left = Duration::ZERO;
+        assert!(app.work_counts());
+        assert!(!app.work_entry().skipped);
+    }
+
+    #[test]
+    fn run_summary_leaves_out_skipped_work() {
+        let config = Config { total_sessions: 3, ..Config::default() };
+        let mut app = PomodoroApp::new(&config);
+        app.start_timer();
+        
+        app.time_left = Duration::from_secs(1);
+        app.tick();
+        app.skip_break();
+        // Skipped halfway: counts toward the run, but isn't a pomodoro
+        app.time_left = Duration::from_secs(600);
+        app.advance_timer();
+        app.skip_break();
+        app.time_left = Duration::from_secs(1);
+        let effects = app.tick();
+        assert!(effects.contains(&notify("All sessions completed! 🎉 Completed 2 pomodoros — 50 focus minutes")));
+        assert!(effects.contains(&Effect::Exit));
+    }
+
+    #[test]
+    fn skip_honors_prompts_and_skip_to_work_does_not() {
+        let config = Config { auto_start_work: false, ..Config::default() };
+        let mut app = PomodoroApp::new(&config);
+        app.start_timer();
+        app.advance_timer();
+        assert_eq!((app.state, app.current_session), (AppState::BreakPrompt, 1));
+        
+        let mut app = PomodoroApp::new(&config);
+        app.start_timer();
+        let effects = app.skip_to_work();
+        assert!(matches!(&effects[..], [Effect::Log(_)]));
+        assert_eq!((app.state, app.timer_type, app.current_session), (AppState::Running, TimerType::Work, 2));
+        
+        // A break that would end at the work prompt
+        app.advance_timer();
+        app.confirm_break();
+        assert_eq!(app.timer_type, TimerType::ShortBreak);
+        app.skip_to_work();
+        assert_eq!((app.state, app.timer_type, app.current_session), (AppState::Running, TimerType::Work, 3));
+    }
+
+    #[test]
+    fn skip_on_the_final_second_ends_the_session_once() {
+        let mut app = PomodoroApp::new(&Config::default());
+        app.start_timer();
+        app.time_left = Duration::from_secs(1);
+        let start = Instant::now();
+        app.sync_clock(start);
+        
+        // The key is handled before the frame's sync
+        let end = start + Duration::from_secs(1);
+        let mut effects = app.skip_at(end);
+        effects.extend(app.sync_clock(end));
+        let logged: Vec<_> = effects.iter().filter_map(|e| if let Effect::Log(entry) = e { Some(entry) } else { None }).collect();
+        assert_eq!(logged.len(), 1);
+        assert!(!logged[0].skipped);
+        assert_eq!((app.state, app.current_session), (AppState::BreakPrompt, 1));
+        
+        // Same for a pause on the last instant of the break
+        app.confirm_break();
+        app.time_left = Duration::from_secs(1);
+        app.sync_clock(start);
+        app.toggle_pause_at(end);
+        assert!(!app.paused);
+        app.sync_clock(end);
+        assert_eq!((app.timer_type, app.current_session, app.paused), (TimerType::Work, 2, false));
+    }
+
+    #[test]
+    fn overtime_counts_up_and_is_logged_once() {
+        let config = Config { overtime: true, ..Config::default() };
+        let mut app = PomodoroApp::new(&config);
+        app.start_timer();
+        app.time_left = Duration::from_secs(1);
+        let start = Instant::now();
+        app.sync_clock(start);
+        
+        let bell = start + Duration::from_secs(1);
+        let effects = app.sync_clock(bell);
+        assert_eq!(app.state, AppState::Overtime);
+        assert_eq!(effects.iter().filter(|e| matches!(e, Effect::Notify(_))).count(), 1);
+        assert!(!effects.iter().any(|e| matches!(e, Effect::Log(_))));
+        assert_eq!(app.overtime_at(bell + Duration::from_secs(90)), Duration::from_secs(90));
+        
+        // Taking the break logs the overtime without a second announcement
+        app.end_overtime(bell + Duration::from_secs(90));
+        let effects = app.advance_timer();
+        assert!(matches!(&effects[..], [Effect::Log(entry)] if entry.overtime_secs == 90 && !entry.skipped));
+        assert_eq!(app.state, AppState::BreakPrompt);
+        
+        // Breaks run out as usual
+        app.confirm_break();
+        app.time_left = Duration::from_secs(1);
+        app.tick();
+        assert_eq!((app.state, app.timer_type), (AppState::Running, TimerType::Work));
+        assert_eq!(app.overtime_at(Instant::now()), Duration::ZERO);
+    }
+
+    #[test]
+    fn long_break_interval_sets_break_sequence() {
+        let sequence = |interval: u32| {
+            let app = PomodoroApp { long_break_interval: interval, ..PomodoroApp::new(&Config::default()) };
+            (1..=6).map(|session| app.break_type_after(session) == TimerType::LongBreak).collect::<Vec<_>>()
+        };
+        assert_eq!(sequence(2), [false, true, false, true, false, true]);
+        assert_eq!(sequence(3), [false, false, true, false, false, true]);
+        assert_eq!(sequence(4), [false, false, false, true, false, false]);
+        assert_eq!(sequence(0), [false; 6]);
+    }
+
+    #[test]
+    fn skipped_work_does_not_earn_a_long_break() {
+        use TimerType::{LongBreak as L, ShortBreak as S};
+        let config = Config {
+            long_break_interval: 2,
+            total_sessions: 8,
+            long_break_needs_completed: true,
+            break_leadin_seconds: 0,
+            ..Default::default()
+        };
+        let mut app = PomodoroApp::new(&config);
+        app.begin(TimerType::Work);
+        let mut breaks = Vec::new();
+        for completed in [false, false, true, false, true, true, false] {
+            app.time_left = if completed { Duration::ZERO } else { Duration::from_secs(60) };
+            app.advance_timer();
+            breaks.push(app.next_break_type.unwrap());
+            app.confirm_break();
+            app.skip_break();
+        }
+        // Positionally sessions 2, 4 and 6 would have had the long break
+        assert_eq!(breaks, [S, S, S, S, L, S, S]);
+    }
+
+    #[test]
+    fn minus_minute_stops_at_type_floor() {
+        let config = Config {
+            min_remaining: config::MinRemaining { work: 300, short_break: 60, long_break: 60 },
+            ..Default::default()
+        };
+        let mut app = PomodoroApp::new(&config);
+        app.time_left = Duration::from_secs(6 * 60 + 30);
+        app.sub_minute();
+        assert_eq!(app.time_left, Duration::from_secs(5 * 60 + 30));
+        app.sub_minute();
+        assert_eq!(app.time_left, Duration::from_secs(5 * 60 + 30));
+        assert!(app.command_message.as_deref().unwrap().starts_with("Minimum reached"));
+
+        app.timer_type = TimerType::ShortBreak;
+        app.sub_minute();
+        assert_eq!(app.time_left, Duration::from_secs(4 * 60 + 30));
+    }
+
+    #[test]
+    fn minute_adjustments_stay_within_bounds() {
+        let config = Config {
+            min_remaining: config::MinRemaining { work: 0, short_break: 0, long_break: 0 },
+            ..Default::default()
+        };
+        let mut app = PomodoroApp::new(&config);
+        app.begin(TimerType::Work);
+        app.time_left = MAX_TIME_LEFT - Duration::from_secs(30);
+        app.session_total = app.time_left;
+        app.add_minute();
+        assert_eq!((app.time_left, app.session_total), (MAX_TIME_LEFT, MAX_TIME_LEFT));
+        app.add_minute();
+        assert_eq!(app.time_left, MAX_TIME_LEFT);
+        assert!(app.command_message.as_deref().unwrap().starts_with("Maximum reached"));
+
+        // A floor of 0 still leaves a minute
+        app.time_left = Duration::from_secs(2 * 60 + 10);
+        app.sub_minute();
+        assert_eq!(app.time_left, Duration::from_secs(70));
+        assert_eq!(app.session_total, MAX_TIME_LEFT - Duration::from_secs(60));
+        app.sub_minute();
+        assert_eq!(app.time_left, Duration::from_secs(70));
+    }
+}
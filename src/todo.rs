@@ -0,0 +1,64 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TodoItem {
+    // Zero-based line number in the source file
+    pub line: usize,
+    pub text: String,
+}
+
+// Incomplete items from a markdown checklist (`- [ ] task`) or, for
+// `.txt` files, todo.txt lines not yet marked with `x `
+pub fn load_todos(path: &Path) -> Vec<TodoItem> {
+    fs::read_to_string(path)
+        .map(|text| parse_todos(&text, is_todo_txt(path)))
+        .unwrap_or_default()
+}
+
+pub fn mark_done(path: &Path, item: &TodoItem) -> io::Result<()> {
+    let text = fs::read_to_string(path)?;
+    let todo_txt = is_todo_txt(path);
+    let mut lines: Vec<&str> = text.split_inclusive('\n').collect();
+
+    // Refuse to touch the file if it changed under us
+    let current = lines.get(item.line).copied().unwrap_or("");
+    if parse_line(current, todo_txt).as_deref() != Some(item.text.as_str()) {
+        return Err(io::Error::other("todo file changed"));
+    }
+
+    let updated = if todo_txt {
+        format!("x {} {}", chrono::Local::now().format("%Y-%m-%d"), current)
+    } else {
+        current.replacen("[ ]", "[x]", 1)
+    };
+    lines[item.line] = &updated;
+    fs::write(path, lines.concat())
+}
+
+fn is_todo_txt(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "txt")
+}
+
+fn parse_todos(text: &str, todo_txt: bool) -> Vec<TodoItem> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(line, content)| {
+            parse_line(content, todo_txt).map(|text| TodoItem { line, text })
+        })
+        .collect()
+}
+
+fn parse_line(line: &str, todo_txt: bool) -> Option<String> {
+    let trimmed = line.trim();
+    for prefix in ["- [ ] ", "* [ ] "] {
+        if let Some(rest) = trimmed.strip_prefix(prefix) {
+            return Some(rest.trim().to_string()).filter(|s| !s.is_empty());
+        }
+    }
+    if todo_txt && !trimmed.is_empty() && !trimmed.starts_with("x ") {
+        return Some(trimmed.to_string());
+    }
+    None
+}
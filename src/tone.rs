@@ -0,0 +1,107 @@
+use serde::Deserialize;
+use std::f64::consts::TAU;
+
+const SAMPLE_RATE: u32 = 22_050;
+const BEEP_MS: u32 = 80;
+// Fade in/out over this many samples so beeps don't click
+const FADE: usize = 200;
+
+// How the final-countdown ticks sound
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CountdownPattern {
+    // The same short beep every second
+    #[default]
+    Single,
+    // One beep per second at first, up to four in the last seconds
+    Accelerating,
+    // One beep per second, pitch climbing an octave towards zero
+    RisingPitch,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tick {
+    pub freq: f64,
+    pub beeps: u32,
+}
+
+// The tick for the second with `secs_left` of the last `window` seconds
+pub fn tick_for(pattern: CountdownPattern, secs_left: u64, window: u64) -> Tick {
+    let window = window.max(1);
+    // 0.0 at the start of the countdown, approaching 1.0 at the end
+    let progress = window.saturating_sub(secs_left) as f64 / window as f64;
+    match pattern {
+        CountdownPattern::Single => Tick { freq: 880.0, beeps: 1 },
+        CountdownPattern::Accelerating => Tick {
+            freq: 880.0,
+            beeps: 1 + (progress * 4.0).min(3.0) as u32,
+        },
+        CountdownPattern::RisingPitch => Tick {
+            freq: 440.0 * (1.0 + progress),
+            beeps: 1,
+        },
+    }
+}
+
+// Mono 16-bit PCM WAV with the tick's beeps spread over one second, so
+// consecutive ticks never overlap
+pub fn render_wav(tick: Tick) -> Vec<u8> {
+    let beeps = tick.beeps.max(1);
+    let spacing = SAMPLE_RATE / beeps;
+    let beep_len = (SAMPLE_RATE * BEEP_MS / 1000) as usize;
+    let total = (spacing * (beeps - 1)) as usize + beep_len;
+
+    let mut samples = vec![0i16; total];
+    for beep in 0..beeps {
+        let start = (spacing * beep) as usize;
+        for i in 0..beep_len {
+            let fade = (i.min(beep_len - 1 - i) as f64 / FADE as f64).min(1.0);
+            let t = i as f64 / SAMPLE_RATE as f64;
+            let value = (TAU * tick.freq * t).sin() * 0.3 * fade;
+            samples[start + i] = (value * i16::MAX as f64) as i16;
+        }
+    }
+
+    let data_len = (samples.len() * 2) as u32;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patterns_build_up_towards_zero() {
+        assert_eq!(tick_for(CountdownPattern::Single, 1, 10).beeps, 1);
+        assert_eq!(tick_for(CountdownPattern::Accelerating, 10, 10).beeps, 1);
+        assert_eq!(tick_for(CountdownPattern::Accelerating, 1, 10).beeps, 4);
+        let low = tick_for(CountdownPattern::RisingPitch, 10, 10).freq;
+        let high = tick_for(CountdownPattern::RisingPitch, 1, 10).freq;
+        assert!(low < high && high < 880.0);
+    }
+
+    #[test]
+    fn wav_fits_in_one_second() {
+        let wav = render_wav(Tick { freq: 880.0, beeps: 4 });
+        assert_eq!(&wav[0..4], b"RIFF");
+        let data_len = u32::from_le_bytes([wav[40], wav[41], wav[42], wav[43]]);
+        assert_eq!(wav.len(), 44 + data_len as usize);
+        assert!(data_len < SAMPLE_RATE * 2);
+    }
+}
@@ -0,0 +1,39 @@
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+
+// Watches the config file for edits. The directory is watched rather than
+// the file itself, since many editors save by replacing the file
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+    events: Receiver<()>,
+}
+
+impl ConfigWatcher {
+    // None if the config directory doesn't exist or watching isn't supported
+    pub fn new(path: &Path) -> Option<Self> {
+        let dir = path.parent().filter(|dir| dir.is_dir())?;
+        let name = path.file_name()?.to_owned();
+        let (tx, events) = mpsc::channel();
+
+        let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+            let Ok(event) = result else {
+                return;
+            };
+            let relevant = event.kind.is_create() || event.kind.is_modify();
+            if relevant && event.paths.iter().any(|p| p.file_name() == Some(name.as_os_str())) {
+                let _ = tx.send(());
+            }
+        })
+        .ok()?;
+        watcher.watch(dir, RecursiveMode::NonRecursive).ok()?;
+
+        Some(Self { _watcher: watcher, events })
+    }
+
+    // True if the file changed since the last call; a burst of events from
+    // one save counts once
+    pub fn changed(&self) -> bool {
+        self.events.try_iter().count() > 0
+    }
+}